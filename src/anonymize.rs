@@ -0,0 +1,136 @@
+//! Anonymized export of logged decisions, for sharing real package
+//! distributions when reporting classification discrepancies.
+//!
+//! The export is one [`DecisionRecord`] per current decision. Handling notes
+//! are left out of the records, and records never carry routing zones or
+//! handling flags, so only the package id needs treatment: it is either
+//! dropped or replaced by a sequential
+//! pseudonym (`anon-1`, `anon-2`, ...). Pseudonyms carry no information about
+//! the ids; the mapping back to them stays with the exporter, in
+//! [`Pseudonyms`].
+
+use std::collections::HashMap;
+
+use crate::audit::AuditEntry;
+use crate::record::DecisionRecord;
+
+/// How package ids are treated on export
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum IdMode {
+    /// Writes every id as `null`
+    #[default]
+    Strip,
+    /// Replaces each id with a pseudonym. Reusing the mode for several
+    /// exports keeps each package's pseudonym.
+    Pseudonymize(Pseudonyms),
+}
+
+/// Sequential pseudonyms assigned to package ids, in order of first use
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Pseudonyms {
+    assigned: HashMap<String, String>,
+}
+
+impl Pseudonyms {
+    /// Returns the pseudonym of a package id, assigning the next one if the
+    /// id hasn't been seen
+    pub fn pseudonym(&mut self, package_id: &str) -> &str {
+        let next = self.assigned.len() + 1;
+        self.assigned
+            .entry(package_id.to_string())
+            .or_insert_with(|| format!("anon-{}", next))
+    }
+
+    /// Looks up the package id behind a pseudonym, e.g. one quoted back in a
+    /// vendor's report
+    pub fn package_id(&self, pseudonym: &str) -> Option<&str> {
+        self.assigned
+            .iter()
+            .find(|(_, assigned)| *assigned == pseudonym)
+            .map(|(id, _)| id.as_str())
+    }
+}
+
+/// Writes the current decisions of a log as anonymized JSON lines, in log
+/// order. Superseded decisions are left out.
+pub fn export(log: &[AuditEntry], mode: &mut IdMode) -> Vec<String> {
+    log.iter()
+        .filter(|entry| entry.is_current())
+        .map(|entry| {
+            let id = match mode {
+                IdMode::Strip => None,
+                IdMode::Pseudonymize(pseudonyms) => Some(pseudonyms.pseudonym(&entry.package_id)),
+            };
+            DecisionRecord::new(id, &entry.package, &entry.decision).to_json()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::Sorter;
+    use crate::{Centimeters, Kilograms, Package, SortCategory};
+
+    fn package(w: f64, h: f64, l: f64, m: f64) -> Package {
+        Package::new(
+            Centimeters::new(w),
+            Centimeters::new(h),
+            Centimeters::new(l),
+            Kilograms::new(m),
+        )
+    }
+
+    #[test]
+    fn test_export_strips_ids_and_notes() {
+        let mut sorter = Sorter::default();
+        sorter
+            .sort("CUST-4711", package(50.0, 50.0, 50.0, 10.0))
+            .unwrap();
+        sorter
+            .add_note("CUST-4711", "deliver to 12 Main St")
+            .unwrap();
+        sorter
+            .reclassify("CUST-4711", package(50.0, 50.0, 50.0, 25.0))
+            .unwrap();
+
+        let lines = export(sorter.log(), &mut IdMode::Strip);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("\"package_id\":null"));
+        assert!(lines[0].contains("\"mass_kg\":25"));
+        assert!(lines[0].contains(SortCategory::Special.as_str()));
+        assert!(!lines[0].contains("CUST-4711"));
+        assert!(!lines[0].contains("Main St"));
+    }
+
+    #[test]
+    fn test_pseudonymized_ids() {
+        let mut sorter = Sorter::default();
+        sorter
+            .sort("CUST-4711", package(10.0, 10.0, 10.0, 1.0))
+            .unwrap();
+        sorter
+            .sort("CUST-4712", package(10.0, 10.0, 10.0, 1.0))
+            .unwrap();
+
+        let mut mode = IdMode::Pseudonymize(Pseudonyms::default());
+        let lines = export(sorter.log(), &mut mode);
+        assert!(lines[0].contains("\"package_id\":\"anon-1\""));
+        assert!(lines[1].contains("\"package_id\":\"anon-2\""));
+        assert!(!lines.concat().contains("CUST"));
+
+        // A later export keeps the pseudonyms and extends them
+        sorter
+            .sort("CUST-0001", package(10.0, 10.0, 10.0, 1.0))
+            .unwrap();
+        let lines = export(sorter.log(), &mut mode);
+        assert!(lines[0].contains("anon-1"));
+        assert!(lines[2].contains("anon-3"));
+
+        let IdMode::Pseudonymize(pseudonyms) = &mode else {
+            unreachable!()
+        };
+        assert_eq!(pseudonyms.package_id("anon-3"), Some("CUST-0001"));
+        assert_eq!(pseudonyms.package_id("anon-4"), None);
+    }
+}
@@ -0,0 +1,691 @@
+//! In-memory decision log with reclassification.
+//!
+//! Packages re-measured at a check station are reclassified rather than
+//! sorted again: the new decision links back to the one it replaces, and the
+//! old decision is kept in the log, marked superseded.
+//!
+//! The log can be saved as JSON lines and loaded back. Each line is the
+//! entry's decision record (see [`crate::record`]) with these fields added:
+//!
+//! | Field            | Type                              |
+//! | ---------------- | --------------------------------- |
+//! | `recorded_at_ms` | number (milliseconds since epoch) |
+//! | `supersedes`     | number (log index) or null        |
+//! | `superseded_by`  | number (log index) or null        |
+//! | `shape`          | `BOX`, `CYLINDER` or `IRREGULAR`  |
+//! | `temperature_c`  | `[min, max]` or null              |
+//! | `fragile`        | boolean                           |
+//! | `hazmat`         | boolean                           |
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::clock::{Clock, SystemClock};
+use crate::json::{self, Value};
+use crate::record::DecisionRecord;
+use crate::{
+    Celsius, Centimeters, Kilograms, Package, Shape, SortCategory, SortDecision, SortRules,
+    TemperatureRange,
+};
+
+/// One decision in the log
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub package_id: String,
+    /// Measurements the decision was made from
+    pub package: Package,
+    pub decision: SortDecision,
+    /// When the decision was made
+    pub recorded_at: SystemTime,
+    /// Free-text handling notes, e.g. "label damaged"
+    pub notes: Vec<String>,
+    /// Log index of the decision this one replaces
+    pub supersedes: Option<usize>,
+    /// Log index of the decision that replaced this one
+    pub superseded_by: Option<usize>,
+}
+
+impl AuditEntry {
+    /// Whether this is the package's current decision
+    pub fn is_current(&self) -> bool {
+        self.superseded_by.is_none()
+    }
+
+    /// Returns the decision as a machine-readable record, with its notes
+    pub fn record(&self) -> DecisionRecord<'_> {
+        DecisionRecord::new(Some(&self.package_id), &self.package, &self.decision)
+            .with_notes(&self.notes)
+    }
+
+    /// Writes the entry as one line of the saved log
+    fn to_log_line(&self) -> String {
+        let index = |i: Option<usize>| i.map_or_else(|| "null".to_string(), |i| i.to_string());
+        let recorded_at_ms = self
+            .recorded_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let shape = match self.package.shape {
+            Shape::Box => "BOX",
+            Shape::Cylinder { .. } => "CYLINDER",
+            Shape::Irregular { .. } => "IRREGULAR",
+        };
+        let temperature = self.package.perishable.map_or_else(
+            || "null".to_string(),
+            |range| {
+                format!(
+                    "[{},{}]",
+                    json::number(range.min.value()),
+                    json::number(range.max.value())
+                )
+            },
+        );
+
+        let mut fields = self.record().fields();
+        fields.extend([
+            ("recorded_at_ms", recorded_at_ms.to_string()),
+            ("supersedes", index(self.supersedes)),
+            ("superseded_by", index(self.superseded_by)),
+            ("shape", json::string(shape)),
+            ("temperature_c", temperature),
+            ("fragile", self.package.fragile.to_string()),
+            ("hazmat", self.package.hazmat.to_string()),
+        ]);
+        json::object(&fields)
+    }
+}
+
+/// A decision read back from a saved log. Only what the log records is
+/// restored: the measurements, the logged category and the notes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedDecision {
+    pub package_id: String,
+    /// Measurements the decision was made from
+    pub package: Package,
+    /// Category in the log
+    pub category: SortCategory,
+    pub rule_version: String,
+    /// When the decision was made, to the millisecond
+    pub recorded_at: SystemTime,
+    pub notes: Vec<String>,
+    /// Whether this was the package's current decision
+    pub current: bool,
+}
+
+impl LoggedDecision {
+    /// Reads one line of a saved log
+    fn from_log_line(line: &str) -> Result<Self, String> {
+        let value = json::parse(line).map_err(|err| err.to_string())?;
+        let field = |name: &str| value.get(name).ok_or(format!("missing {:?}", name));
+        let number = |name: &str| {
+            field(name)?
+                .as_f64()
+                .ok_or(format!("{:?} is not a number", name))
+        };
+        let string = |name: &str| {
+            field(name)?
+                .as_str()
+                .ok_or(format!("{:?} is not a string", name))
+        };
+        let flag = |name: &str| {
+            field(name)?
+                .as_bool()
+                .ok_or(format!("{:?} is not a boolean", name))
+        };
+
+        let (width, height, length) = (
+            Centimeters::new(number("width_cm")?),
+            Centimeters::new(number("height_cm")?),
+            Centimeters::new(number("length_cm")?),
+        );
+        let mass = Kilograms::new(number("mass_kg")?);
+        let mut package = match string("shape")? {
+            "BOX" => Package::new(width, height, length, mass),
+            "CYLINDER" => Package::cylinder(width, length, mass),
+            "IRREGULAR" => Package::irregular([width, height, length], mass),
+            other => return Err(format!("unknown shape {:?}", other)),
+        };
+        match field("temperature_c")? {
+            Value::Null => {}
+            range => {
+                let [min, max] = range
+                    .as_array()
+                    .and_then(|range| range.iter().map(Value::as_f64).collect::<Option<Vec<_>>>())
+                    .and_then(|range| <[f64; 2]>::try_from(range).ok())
+                    .ok_or("\"temperature_c\" is not [min, max]")?;
+                package = package.with_temperature_range(TemperatureRange::new(
+                    Celsius::new(min),
+                    Celsius::new(max),
+                ));
+            }
+        }
+        package.fragile = flag("fragile")?;
+        package.hazmat = flag("hazmat")?;
+
+        let recorded_at_ms = number("recorded_at_ms")?;
+        if !(recorded_at_ms.is_finite() && recorded_at_ms >= 0.0) {
+            return Err("\"recorded_at_ms\" is not a timestamp".to_string());
+        }
+        let notes = field("notes")?
+            .as_array()
+            .and_then(|notes| {
+                notes
+                    .iter()
+                    .map(|note| note.as_str().map(String::from))
+                    .collect()
+            })
+            .ok_or("\"notes\" is not an array of strings")?;
+
+        Ok(Self {
+            package_id: string("package_id")?.to_string(),
+            package,
+            category: string("category")?
+                .parse()
+                .map_err(|err| format!("{}", err))?,
+            rule_version: string("rule_version")?.to_string(),
+            recorded_at: UNIX_EPOCH + Duration::from_millis(recorded_at_ms as u64),
+            notes,
+            current: matches!(field("superseded_by")?, Value::Null),
+        })
+    }
+}
+
+/// Sorts packages under a fixed rule set, recording every decision.
+/// Scheduled overrides are applied as of the sorter's clock.
+#[derive(Debug, Clone)]
+pub struct Sorter {
+    pub rules: SortRules,
+    clock: Arc<dyn Clock>,
+    log: Vec<AuditEntry>,
+}
+
+impl Default for Sorter {
+    fn default() -> Self {
+        Self::new(SortRules::default())
+    }
+}
+
+impl Sorter {
+    /// Creates a sorter with an empty log, using the system clock
+    pub fn new(rules: SortRules) -> Self {
+        Self::with_clock(rules, Arc::new(SystemClock))
+    }
+
+    /// Creates a sorter with an empty log, using the given clock
+    pub fn with_clock(rules: SortRules, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            rules,
+            clock,
+            log: Vec::new(),
+        }
+    }
+
+    /// Sorts a package seen for the first time
+    pub fn sort(&mut self, package_id: &str, package: Package) -> Result<&AuditEntry, AuditError> {
+        if self.current_index(package_id).is_some() {
+            return Err(AuditError::DuplicatePackage(package_id.to_string()));
+        }
+        Ok(self.record(package_id, package, None))
+    }
+
+    /// Attaches a free-text note to the package's current decision. Notes
+    /// carry over when the package is reclassified.
+    pub fn add_note(
+        &mut self,
+        package_id: &str,
+        note: impl Into<String>,
+    ) -> Result<(), AuditError> {
+        let current = self
+            .current_index(package_id)
+            .ok_or_else(|| AuditError::UnknownPackage(package_id.to_string()))?;
+        self.log[current].notes.push(note.into());
+        Ok(())
+    }
+
+    /// Re-sorts a package from new measurements, superseding its current
+    /// decision
+    pub fn reclassify(
+        &mut self,
+        package_id: &str,
+        new_measurements: Package,
+    ) -> Result<&AuditEntry, AuditError> {
+        let previous = self
+            .current_index(package_id)
+            .ok_or_else(|| AuditError::UnknownPackage(package_id.to_string()))?;
+        self.log[previous].superseded_by = Some(self.log.len());
+        Ok(self.record(package_id, new_measurements, Some(previous)))
+    }
+
+    /// Returns the package's current decision
+    pub fn current(&self, package_id: &str) -> Option<&AuditEntry> {
+        self.current_index(package_id).map(|i| &self.log[i])
+    }
+
+    /// Returns every decision made for the package, oldest first
+    pub fn history(&self, package_id: &str) -> Vec<&AuditEntry> {
+        self.log
+            .iter()
+            .filter(|e| e.package_id == package_id)
+            .collect()
+    }
+
+    /// Returns the full log in the order decisions were made
+    pub fn log(&self) -> &[AuditEntry] {
+        &self.log
+    }
+
+    /// Writes the full log as JSON lines, one entry per line
+    pub fn write_log(&self, out: &mut impl Write) -> io::Result<()> {
+        for entry in &self.log {
+            writeln!(out, "{}", entry.to_log_line())?;
+        }
+        Ok(())
+    }
+
+    /// Saves the full log to a file, replacing it
+    pub fn save_log(&self, path: &Path) -> Result<(), AuditLogError> {
+        let io_error = |source| AuditLogError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let mut out = io::BufWriter::new(fs::File::create(path).map_err(io_error)?);
+        self.write_log(&mut out)
+            .and_then(|()| out.flush())
+            .map_err(io_error)
+    }
+
+    /// Re-sorts every package's current measurements under `rules`, as of
+    /// the time each was recorded, and reports the packages whose category
+    /// would change. The log itself is left untouched, so rule changes can be
+    /// regression-checked before deployment.
+    pub fn replay(&self, rules: &SortRules) -> Vec<CategoryChange> {
+        self.log
+            .iter()
+            .filter(|e| e.is_current())
+            .filter_map(|e| {
+                CategoryChange::replay(
+                    &e.package_id,
+                    &e.package,
+                    e.decision.category,
+                    e.recorded_at,
+                    rules,
+                )
+            })
+            .collect()
+    }
+
+    fn current_index(&self, package_id: &str) -> Option<usize> {
+        self.log
+            .iter()
+            .position(|e| e.package_id == package_id && e.is_current())
+    }
+
+    fn record(
+        &mut self,
+        package_id: &str,
+        package: Package,
+        supersedes: Option<usize>,
+    ) -> &AuditEntry {
+        let notes = supersedes.map_or_else(Vec::new, |i| self.log[i].notes.clone());
+        let recorded_at = self.clock.now();
+        self.log.push(AuditEntry {
+            package_id: package_id.to_string(),
+            package,
+            decision: self.rules.at(recorded_at).decide(&package),
+            recorded_at,
+            notes,
+            supersedes,
+            superseded_by: None,
+        });
+        &self.log[self.log.len() - 1]
+    }
+}
+
+/// A package whose category differs under replayed rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryChange {
+    pub package_id: String,
+    /// Category in the log
+    pub before: SortCategory,
+    /// Category under the replayed rules
+    pub after: SortCategory,
+}
+
+impl CategoryChange {
+    /// Re-sorts one logged decision as of when it was recorded, returning the
+    /// change if the category differs
+    fn replay(
+        package_id: &str,
+        package: &Package,
+        before: SortCategory,
+        recorded_at: SystemTime,
+        rules: &SortRules,
+    ) -> Option<Self> {
+        let after = rules.at(recorded_at).decide(package).category;
+        (after != before).then(|| CategoryChange {
+            package_id: package_id.to_string(),
+            before,
+            after,
+        })
+    }
+
+    /// Writes the change as a single-line JSON object with the stable
+    /// category identifiers
+    pub fn to_json(&self) -> String {
+        json::object(&[
+            ("package_id", json::string(&self.package_id)),
+            ("before", json::string(self.before.as_str())),
+            ("after", json::string(self.after.as_str())),
+        ])
+    }
+}
+
+/// Re-sorts the current decisions of a saved log under `rules`, like
+/// [`Sorter::replay`], and reports the packages whose category would change
+pub fn replay_log(log: &[LoggedDecision], rules: &SortRules) -> Vec<CategoryChange> {
+    log.iter()
+        .filter(|logged| logged.current)
+        .filter_map(|logged| {
+            CategoryChange::replay(
+                &logged.package_id,
+                &logged.package,
+                logged.category,
+                logged.recorded_at,
+                rules,
+            )
+        })
+        .collect()
+}
+
+/// Error returned when a package id doesn't match the log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditError {
+    /// The package was already sorted; reclassify it instead
+    DuplicatePackage(String),
+    /// The package was never sorted
+    UnknownPackage(String),
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditError::DuplicatePackage(id) => write!(f, "package {id:?} was already sorted"),
+            AuditError::UnknownPackage(id) => write!(f, "package {id:?} was never sorted"),
+        }
+    }
+}
+
+impl std::error::Error for AuditError {}
+
+/// Reads a log written by [`Sorter::write_log`]. Blank lines are skipped.
+pub fn read_log(text: &str) -> Result<Vec<LoggedDecision>, AuditLogError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            LoggedDecision::from_log_line(line).map_err(|message| AuditLogError::Syntax {
+                line: index + 1,
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Loads a log saved by [`Sorter::save_log`]
+pub fn load_log(path: &Path) -> Result<Vec<LoggedDecision>, AuditLogError> {
+    let text = fs::read_to_string(path).map_err(|source| AuditLogError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    read_log(&text)
+}
+
+/// Error returned when a saved log can't be written or read
+#[derive(Debug)]
+pub enum AuditLogError {
+    /// The log file couldn't be written or read
+    Io { path: PathBuf, source: io::Error },
+    /// A log line isn't a valid log entry
+    Syntax { line: usize, message: String },
+}
+
+impl fmt::Display for AuditLogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditLogError::Io { path, source } => {
+                write!(f, "failed to access {}: {}", path.display(), source)
+            }
+            AuditLogError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for AuditLogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AuditLogError::Io { source, .. } => Some(source),
+            AuditLogError::Syntax { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TestClock;
+    use crate::schedule::{RuleOverride, Schedule};
+    use crate::{Centimeters, Kilograms};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn package(width: f64, height: f64, length: f64, mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(width),
+            Centimeters::new(height),
+            Centimeters::new(length),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_reclassify_supersedes() {
+        let mut sorter = Sorter::default();
+        let first = sorter
+            .sort("PKG-1", package(160.0, 50.0, 50.0, 25.0))
+            .unwrap();
+        assert_eq!(first.decision.category, SortCategory::Rejected);
+        sorter
+            .sort("PKG-2", package(10.0, 10.0, 10.0, 1.0))
+            .unwrap();
+
+        let second = sorter
+            .reclassify("PKG-1", package(140.0, 50.0, 50.0, 25.0))
+            .unwrap();
+        assert_eq!(second.decision.category, SortCategory::Special);
+        assert_eq!(second.supersedes, Some(0));
+
+        let history = sorter.history("PKG-1");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].superseded_by, Some(2));
+        assert!(!history[0].is_current());
+        assert_eq!(
+            sorter.current("PKG-1").unwrap().decision.category,
+            SortCategory::Special
+        );
+        assert_eq!(sorter.log().len(), 3);
+    }
+
+    #[test]
+    fn test_notes_carry_over() {
+        let mut sorter = Sorter::default();
+        sorter
+            .sort("PKG-1", package(10.0, 10.0, 10.0, 1.0))
+            .unwrap();
+        sorter.add_note("PKG-1", "label damaged").unwrap();
+        sorter
+            .reclassify("PKG-1", package(10.0, 10.0, 12.0, 1.0))
+            .unwrap();
+        sorter.add_note("PKG-1", "re-taped").unwrap();
+
+        let history = sorter.history("PKG-1");
+        assert_eq!(history[0].notes, ["label damaged"]);
+        assert_eq!(history[1].notes, ["label damaged", "re-taped"]);
+        assert!(sorter.add_note("PKG-2", "lost").is_err());
+    }
+
+    #[test]
+    fn test_scheduled_rules_follow_clock() {
+        // 2024-12-01 00:00 UTC
+        let december = UNIX_EPOCH + Duration::from_secs(1_733_011_200);
+        let clock = Arc::new(TestClock::new(december - Duration::from_secs(86_400)));
+        let rules = SortRules {
+            schedule: vec![RuleOverride {
+                name: "peak".to_string(),
+                schedule: Schedule {
+                    dates: Some(("2024-12-01".parse().unwrap(), "2024-12-24".parse().unwrap())),
+                    hours: None,
+                },
+                mass_threshold: Some(Kilograms::new(15.0)),
+                ..RuleOverride::default()
+            }],
+            ..SortRules::default()
+        };
+        let mut sorter = Sorter::with_clock(rules, clock.clone());
+
+        let entry = sorter
+            .sort("PKG-1", package(30.0, 30.0, 30.0, 18.0))
+            .unwrap();
+        assert_eq!(entry.decision.category, SortCategory::Standard);
+
+        clock.set(december);
+        let entry = sorter
+            .sort("PKG-2", package(30.0, 30.0, 30.0, 18.0))
+            .unwrap();
+        assert_eq!(entry.decision.category, SortCategory::Special);
+        assert_eq!(entry.decision.rule_version, "base+peak");
+        assert_eq!(entry.recorded_at, december);
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut sorter = Sorter::default();
+        sorter
+            .sort("PKG-1", package(50.0, 50.0, 50.0, 22.0))
+            .unwrap();
+        sorter
+            .sort("PKG-2", package(50.0, 50.0, 50.0, 10.0))
+            .unwrap();
+        sorter
+            .sort("PKG-3", package(50.0, 50.0, 50.0, 30.0))
+            .unwrap();
+        // Superseded decisions aren't replayed
+        sorter
+            .reclassify("PKG-3", package(50.0, 50.0, 50.0, 5.0))
+            .unwrap();
+
+        let rules = SortRules {
+            mass_threshold: Kilograms::new(25.0),
+            ..SortRules::default()
+        };
+        let changes = sorter.replay(&rules);
+        assert_eq!(
+            changes,
+            [CategoryChange {
+                package_id: "PKG-1".to_string(),
+                before: SortCategory::Special,
+                after: SortCategory::Standard,
+            }]
+        );
+        assert_eq!(sorter.log().len(), 4);
+        assert_eq!(
+            changes[0].to_json(),
+            r#"{"package_id":"PKG-1","before":"SPECIAL","after":"STANDARD"}"#
+        );
+
+        // A saved log replays the same way
+        let mut saved = Vec::new();
+        sorter.write_log(&mut saved).unwrap();
+        let loaded = read_log(&String::from_utf8(saved).unwrap()).unwrap();
+        assert_eq!(replay_log(&loaded, &rules), changes);
+    }
+
+    #[test]
+    fn test_audit_errors() {
+        let mut sorter = Sorter::default();
+        assert_eq!(
+            sorter
+                .reclassify("PKG-1", package(10.0, 10.0, 10.0, 1.0))
+                .unwrap_err(),
+            AuditError::UnknownPackage("PKG-1".to_string())
+        );
+        sorter
+            .sort("PKG-1", package(10.0, 10.0, 10.0, 1.0))
+            .unwrap();
+        assert_eq!(
+            sorter
+                .sort("PKG-1", package(10.0, 10.0, 10.0, 1.0))
+                .unwrap_err(),
+            AuditError::DuplicatePackage("PKG-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_log_round_trip() {
+        let clock = Arc::new(TestClock::new(
+            UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        ));
+        let mut sorter = Sorter::with_clock(SortRules::default(), clock);
+        sorter
+            .sort("PKG-1", package(10.0, 10.0, 10.0, 1.0))
+            .unwrap();
+        sorter.add_note("PKG-1", "label \"damaged\"").unwrap();
+        sorter
+            .reclassify("PKG-1", package(160.0, 10.0, 10.0, 1.0).with_fragile())
+            .unwrap();
+        sorter
+            .add_note("PKG-1", "re-taped\nby night shift")
+            .unwrap();
+        let chilled = Package::cylinder(
+            Centimeters::new(12.5),
+            Centimeters::new(80.0),
+            Kilograms::new(0.3),
+        )
+        .with_temperature_range(TemperatureRange::new(Celsius::new(2.0), Celsius::new(8.0)));
+        sorter.sort("PKG-2", chilled).unwrap();
+
+        let mut saved = Vec::new();
+        sorter.write_log(&mut saved).unwrap();
+        let loaded = read_log(&String::from_utf8(saved).unwrap()).unwrap();
+
+        assert_eq!(loaded.len(), 3);
+        for (logged, entry) in loaded.iter().zip(sorter.log()) {
+            assert_eq!(logged.package_id, entry.package_id);
+            assert_eq!(logged.package, entry.package);
+            assert_eq!(logged.category, entry.decision.category);
+            assert_eq!(logged.rule_version, entry.decision.rule_version);
+            assert_eq!(logged.recorded_at, entry.recorded_at);
+            assert_eq!(logged.notes, entry.notes);
+            assert_eq!(logged.current, entry.is_current());
+        }
+        assert_eq!(
+            loaded[1].notes,
+            ["label \"damaged\"", "re-taped\nby night shift"]
+        );
+    }
+
+    #[test]
+    fn test_log_errors() {
+        assert!(read_log("\n  \n").unwrap().is_empty());
+        for (text, expected) in [("{", "line 1: invalid JSON"), ("\n{}", "line 2: missing")] {
+            let err = read_log(text).unwrap_err();
+            assert!(err.to_string().starts_with(expected), "{}", err);
+        }
+        assert!(matches!(
+            load_log(Path::new("/nonexistent/audit.jsonl")),
+            Err(AuditLogError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! Weight-balance checks for planned pallet and cart loads.
+
+use crate::pallet::{Pallet, PalletSpec};
+use crate::{Centimeters, Package};
+
+/// How far the center of mass of a load may sit from the middle of the deck
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BalanceEnvelope {
+    /// Maximum offset from the deck center across its width
+    pub max_offset_x: Centimeters,
+    /// Maximum offset from the deck center along its length
+    pub max_offset_z: Centimeters,
+    /// Maximum height of the center of mass above the deck
+    pub max_height: Centimeters,
+}
+
+/// Where the center of mass of a load sits and whether it is acceptable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BalanceReport {
+    /// Center of mass across the deck width, from the deck corner
+    pub x: Centimeters,
+    /// Height of the center of mass above the deck
+    pub y: Centimeters,
+    /// Center of mass along the deck length, from the deck corner
+    pub z: Centimeters,
+    /// Signed offset of the center of mass from the deck center across its width
+    pub offset_x: Centimeters,
+    /// Signed offset of the center of mass from the deck center along its length
+    pub offset_z: Centimeters,
+    /// Whether the center of mass lies inside the envelope
+    pub balanced: bool,
+}
+
+/// Computes the center of mass of a planned pallet (or cart) load and checks
+/// it against the envelope.
+///
+/// Each package is treated as having its mass evenly distributed, so its own
+/// center of mass is the middle of its placement. `packages` is the slice the
+/// pallet was planned from. An empty load is reported balanced at the deck
+/// center.
+pub fn check_balance(
+    pallet: &Pallet,
+    packages: &[Package],
+    spec: &PalletSpec,
+    envelope: &BalanceEnvelope,
+) -> BalanceReport {
+    let center_x = spec.width.value() / 2.0;
+    let center_z = spec.length.value() / 2.0;
+
+    let mut total = 0.0;
+    let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+    for placement in pallet.placements() {
+        let mass = packages[placement.item].mass.value();
+        total += mass;
+        x += mass * (placement.x.value() + placement.width.value() / 2.0);
+        y += mass * (placement.y.value() + placement.height.value() / 2.0);
+        z += mass * (placement.z.value() + placement.length.value() / 2.0);
+    }
+    let (x, y, z) = if total > 0.0 {
+        (x / total, y / total, z / total)
+    } else {
+        (center_x, 0.0, center_z)
+    };
+
+    let offset_x = x - center_x;
+    let offset_z = z - center_z;
+    BalanceReport {
+        x: Centimeters::new(x),
+        y: Centimeters::new(y),
+        z: Centimeters::new(z),
+        offset_x: Centimeters::new(offset_x),
+        offset_z: Centimeters::new(offset_z),
+        balanced: offset_x.abs() <= envelope.max_offset_x.value()
+            && offset_z.abs() <= envelope.max_offset_z.value()
+            && y <= envelope.max_height.value(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packing::Placement;
+    use crate::pallet::Layer;
+    use crate::{Kilograms, SortCategory};
+
+    fn spec() -> PalletSpec {
+        PalletSpec::new(
+            Centimeters::new(80.0),
+            Centimeters::new(120.0),
+            Centimeters::new(150.0),
+            Kilograms::new(500.0),
+        )
+    }
+
+    fn envelope() -> BalanceEnvelope {
+        BalanceEnvelope {
+            max_offset_x: Centimeters::new(10.0),
+            max_offset_z: Centimeters::new(15.0),
+            max_height: Centimeters::new(60.0),
+        }
+    }
+
+    fn placement(item: usize, x: f64, z: f64) -> Placement {
+        Placement {
+            item,
+            x: Centimeters::new(x),
+            y: Centimeters::new(0.0),
+            z: Centimeters::new(z),
+            width: Centimeters::new(40.0),
+            height: Centimeters::new(20.0),
+            length: Centimeters::new(40.0),
+        }
+    }
+
+    fn pallet(placements: Vec<Placement>) -> Pallet {
+        Pallet {
+            category: SortCategory::Standard,
+            layers: vec![Layer {
+                y: Centimeters::new(0.0),
+                height: Centimeters::new(20.0),
+                placements,
+            }],
+            mass: Kilograms::new(0.0),
+        }
+    }
+
+    fn package(mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(40.0),
+            Centimeters::new(20.0),
+            Centimeters::new(40.0),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_balanced_load() {
+        let packages = [package(10.0), package(10.0)];
+        let pallet = pallet(vec![placement(0, 0.0, 40.0), placement(1, 40.0, 40.0)]);
+        let report = check_balance(&pallet, &packages, &spec(), &envelope());
+
+        assert_eq!(report.x, Centimeters::new(40.0));
+        assert_eq!(report.y, Centimeters::new(10.0));
+        assert_eq!(report.z, Centimeters::new(60.0));
+        assert!(report.balanced);
+    }
+
+    #[test]
+    fn test_lopsided_load() {
+        // A heavy package on the left outweighs a light one on the right
+        let packages = [package(30.0), package(2.0)];
+        let pallet = pallet(vec![placement(0, 0.0, 40.0), placement(1, 40.0, 40.0)]);
+        let report = check_balance(&pallet, &packages, &spec(), &envelope());
+
+        assert_eq!(report.x, Centimeters::new(22.5));
+        assert_eq!(report.offset_x, Centimeters::new(-17.5));
+        assert!(!report.balanced);
+    }
+}
@@ -0,0 +1,284 @@
+//! CSV batch files, read past malformed rows.
+//!
+//! The first non-blank line is the header; measurement columns are resolved
+//! from it with [`ColumnMap::detect`]. A row that can't be read doesn't stop
+//! the batch: it is kept as a [`RowError`] with its line number, raw text and
+//! reason, and reading continues. The errors can be written as a JSON-lines
+//! sidecar file, one `{"line", "raw", "reason"}` object per row.
+//!
+//! Fields are separated by commas. A field may be quoted with `"` to hold
+//! commas (e.g. `"1,5"` with a comma-decimal number format), with `""` for a
+//! literal quote.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::columns::{AmbiguousColumnsError, ColumnMap};
+use crate::json;
+use crate::numbers::ParseMode;
+use crate::Package;
+
+/// The rows of a batch file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    /// Packages read, with the line each came from
+    pub packages: Vec<(usize, Package)>,
+    /// Rows that couldn't be read, in file order
+    pub errors: Vec<RowError>,
+}
+
+impl Batch {
+    /// Number of data rows in the file, read or not
+    pub fn rows(&self) -> usize {
+        self.packages.len() + self.errors.len()
+    }
+
+    /// Writes the row errors as JSON lines
+    pub fn write_errors(&self, out: &mut impl Write) -> io::Result<()> {
+        for error in &self.errors {
+            writeln!(out, "{}", error.to_json())?;
+        }
+        Ok(())
+    }
+
+    /// Saves the row errors to a sidecar file, replacing it
+    pub fn save_errors(&self, path: &Path) -> Result<(), BatchError> {
+        let io_error = |source| BatchError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let mut out = io::BufWriter::new(fs::File::create(path).map_err(io_error)?);
+        self.write_errors(&mut out)
+            .and_then(|()| out.flush())
+            .map_err(io_error)
+    }
+}
+
+/// A row that couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// Line number in the file, starting at 1
+    pub line: usize,
+    /// The row as it appears in the file
+    pub raw: String,
+    pub reason: String,
+}
+
+impl RowError {
+    /// Writes the error as a single-line JSON object
+    pub fn to_json(&self) -> String {
+        json::object(&[
+            ("line", self.line.to_string()),
+            ("raw", json::string(&self.raw)),
+            ("reason", json::string(&self.reason)),
+        ])
+    }
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Reads a CSV batch, resolving columns from the header with `columns` and
+/// parsing numbers with `mode`
+pub fn read_csv(text: &str, columns: &ColumnMap, mode: ParseMode) -> Result<Batch, BatchError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (line, raw) = lines.next().ok_or(BatchError::MissingHeader)?;
+    let header = split_fields(raw).map_err(|reason| BatchError::Header { line, reason })?;
+    let header: Vec<&str> = header.iter().map(String::as_str).collect();
+    let columns = columns
+        .detect(&header)
+        .map_err(BatchError::AmbiguousColumns)?;
+
+    let mut batch = Batch {
+        packages: Vec::new(),
+        errors: Vec::new(),
+    };
+    for (line, raw) in lines {
+        let package = split_fields(raw).and_then(|row| {
+            if row.len() != header.len() {
+                return Err(format!(
+                    "expected {} fields, got {}",
+                    header.len(),
+                    row.len()
+                ));
+            }
+            let row: Vec<&str> = row.iter().map(String::as_str).collect();
+            columns
+                .package_from_row(&header, &row, mode)
+                .map_err(|errors| {
+                    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                    errors.join("; ")
+                })
+        });
+        match package {
+            Ok(package) => batch.packages.push((line, package)),
+            Err(reason) => batch.errors.push(RowError {
+                line,
+                raw: raw.to_string(),
+                reason,
+            }),
+        }
+    }
+    Ok(batch)
+}
+
+/// Loads a CSV batch file, like [`read_csv`]
+pub fn load_csv(path: &Path, columns: &ColumnMap, mode: ParseMode) -> Result<Batch, BatchError> {
+    let text = fs::read_to_string(path).map_err(|source| BatchError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    read_csv(&text, columns, mode)
+}
+
+/// Splits a CSV line into fields, unquoting quoted ones
+fn split_fields(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Error returned when a batch file can't be read at all
+#[derive(Debug)]
+pub enum BatchError {
+    /// The file couldn't be written or read
+    Io { path: PathBuf, source: io::Error },
+    /// The file has no header line
+    MissingHeader,
+    /// The header line isn't valid CSV
+    Header { line: usize, reason: String },
+    /// The header has more than one column for a field
+    AmbiguousColumns(AmbiguousColumnsError),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Io { path, source } => {
+                write!(f, "failed to access {}: {}", path.display(), source)
+            }
+            BatchError::MissingHeader => write!(f, "batch file has no header line"),
+            BatchError::Header { line, reason } => write!(f, "line {}: {}", line, reason),
+            BatchError::AmbiguousColumns(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BatchError::Io { source, .. } => Some(source),
+            BatchError::AmbiguousColumns(err) => Some(err),
+            BatchError::MissingHeader | BatchError::Header { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numbers::NumberFormat;
+    use crate::{Centimeters, Kilograms};
+
+    #[test]
+    fn test_read_csv_past_bad_rows() {
+        let text = "sku,width_in,height,length,mass_kg\n\
+                    A1,10,20,30,5\n\
+                    \n\
+                    A2,10,x,30,-1\n\
+                    A3,10,20\n\
+                    A4,1,2,3,4\n";
+        let batch = read_csv(text, &ColumnMap::default(), ParseMode::Strict).unwrap();
+
+        assert_eq!(batch.rows(), 4);
+        let lines: Vec<usize> = batch.packages.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [2, 6]);
+        assert_eq!(batch.packages[0].1.width, Centimeters::new(25.4));
+
+        assert_eq!(batch.errors.len(), 2);
+        assert_eq!(batch.errors[0].line, 4);
+        assert_eq!(batch.errors[0].raw, "A2,10,x,30,-1");
+        assert!(batch.errors[0].reason.starts_with("height: "));
+        assert!(batch.errors[0].reason.ends_with("; mass: out of range"));
+        assert_eq!(batch.errors[1].reason, "expected 5 fields, got 3");
+
+        let mut sidecar = Vec::new();
+        batch.write_errors(&mut sidecar).unwrap();
+        let sidecar = String::from_utf8(sidecar).unwrap();
+        assert_eq!(
+            sidecar.lines().nth(1),
+            Some(r#"{"line":5,"raw":"A3,10,20","reason":"expected 5 fields, got 3"}"#)
+        );
+    }
+
+    #[test]
+    fn test_quoted_fields() {
+        let text = "width,height,length,mass\n\"1,5\",2,3,\"4\"\n\"1,5,2,3,4\n";
+        let batch = read_csv(
+            text,
+            &ColumnMap::default(),
+            ParseMode::Format(NumberFormat::COMMA),
+        )
+        .unwrap();
+        assert_eq!(batch.packages[0].1.width, Centimeters::new(1.5));
+        assert_eq!(batch.packages[0].1.mass, Kilograms::new(4.0));
+        assert_eq!(batch.errors[0].reason, "unterminated quoted field");
+        assert_eq!(
+            split_fields(r#"a,"say ""hi""",b"#).unwrap(),
+            ["a", "say \"hi\"", "b"]
+        );
+    }
+
+    #[test]
+    fn test_batch_errors() {
+        let columns = ColumnMap::default();
+        assert!(matches!(
+            read_csv("\n\n", &columns, ParseMode::Strict),
+            Err(BatchError::MissingHeader)
+        ));
+        assert!(matches!(
+            read_csv("mass_kg,mass_lb\n1,2\n", &columns, ParseMode::Strict),
+            Err(BatchError::AmbiguousColumns(_))
+        ));
+        assert!(matches!(
+            load_csv(
+                Path::new("/nonexistent/batch.csv"),
+                &columns,
+                ParseMode::Strict
+            ),
+            Err(BatchError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+//! Throughput benchmark over seeded synthetic packages, for capacity
+//! planning at new sites.
+
+use std::fmt::{self, Write};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{Celsius, Centimeters, Kilograms, Package, SortDecision, SortRules, TemperatureRange};
+
+/// Number of packages benchmarked by default
+pub const DEFAULT_PACKAGES: usize = 100_000;
+
+/// Seed used by default, so runs on different machines are comparable
+pub const DEFAULT_SEED: u64 = 42;
+
+/// Timings of one benchmark run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchReport {
+    pub packages: usize,
+    /// Time spent generating the synthetic packages
+    pub generate: Duration,
+    /// Time spent classifying
+    pub classify: Duration,
+    /// Time spent formatting the output lines
+    pub output: Duration,
+}
+
+impl BenchReport {
+    /// Classification and output throughput in packages per second
+    pub fn packages_per_sec(&self) -> f64 {
+        let elapsed = self.classify + self.output;
+        if elapsed.is_zero() {
+            return 0.0;
+        }
+        self.packages as f64 / elapsed.as_secs_f64()
+    }
+
+    /// Average latency of a stage per package
+    pub fn per_package(&self, stage: Duration) -> Duration {
+        Duration::from_secs_f64(stage.as_secs_f64() / self.packages.max(1) as f64)
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "packages:     {}", self.packages)?;
+        for (stage, duration) in [
+            ("generate", self.generate),
+            ("classify", self.classify),
+            ("output", self.output),
+        ] {
+            writeln!(
+                f,
+                "{:<13} {:?} total, {:?}/package",
+                format!("{}:", stage),
+                duration,
+                self.per_package(duration)
+            )?;
+        }
+        write!(
+            f,
+            "throughput:   {:.0} packages/sec",
+            self.packages_per_sec()
+        )
+    }
+}
+
+/// Generates `count` pseudo-random packages; the same seed always yields the
+/// same packages. Dimensions range from 1 to 200 cm and mass from 0.1 to
+/// 60 kg, and about one package in ten is chilled (2–8 °C), so every
+/// category is represented.
+pub fn synthetic_packages(count: usize, seed: u64) -> Vec<Package> {
+    let mut state = seed;
+    let mut next = move || {
+        // SplitMix64
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        (z ^ (z >> 31)) as f64 / u64::MAX as f64
+    };
+
+    (0..count)
+        .map(|_| {
+            let package = Package::new(
+                Centimeters::new(1.0 + next() * 199.0),
+                Centimeters::new(1.0 + next() * 199.0),
+                Centimeters::new(1.0 + next() * 199.0),
+                Kilograms::new(0.1 + next() * 59.9),
+            );
+            if next() < 0.1 {
+                package.with_temperature_range(TemperatureRange::new(
+                    Celsius::new(2.0),
+                    Celsius::new(8.0),
+                ))
+            } else {
+                package
+            }
+        })
+        .collect()
+}
+
+/// Generates, classifies and formats `count` packages under the rules,
+/// timing each stage
+pub fn run(count: usize, seed: u64, rules: &SortRules) -> BenchReport {
+    let start = Instant::now();
+    let packages = synthetic_packages(count, seed);
+    let generate = start.elapsed();
+
+    let start = Instant::now();
+    let decisions: Vec<SortDecision> = packages.iter().map(|p| rules.decide(p)).collect();
+    let classify = start.elapsed();
+
+    let start = Instant::now();
+    let mut line = String::new();
+    for (package, decision) in packages.iter().zip(&decisions) {
+        line.clear();
+        let _ = write!(
+            line,
+            "{:.1}x{:.1}x{:.1} {:.1} -> {}",
+            package.width.value(),
+            package.height.value(),
+            package.length.value(),
+            package.mass.value(),
+            decision.category
+        );
+        black_box(&line);
+    }
+    let output = start.elapsed();
+
+    BenchReport {
+        packages: count,
+        generate,
+        classify,
+        output,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortCategory;
+
+    #[test]
+    fn test_synthetic_packages_are_seeded() {
+        let packages = synthetic_packages(1_000, 7);
+        assert_eq!(packages, synthetic_packages(1_000, 7));
+        assert_ne!(packages, synthetic_packages(1_000, 8));
+
+        let categories: Vec<SortCategory> = packages.iter().map(Package::sort_category).collect();
+        for category in [
+            SortCategory::Standard,
+            SortCategory::Special,
+            SortCategory::Perishable,
+            SortCategory::Rejected,
+        ] {
+            assert!(categories.contains(&category));
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let report = run(100, DEFAULT_SEED, &SortRules::default());
+        assert_eq!(report.packages, 100);
+        assert!(report.packages_per_sec() > 0.0);
+        assert!(report.to_string().contains("packages/sec"));
+
+        // Nothing classified in no time is no throughput, not NaN
+        let report = BenchReport {
+            packages: 0,
+            generate: Duration::ZERO,
+            classify: Duration::ZERO,
+            output: Duration::ZERO,
+        };
+        assert_eq!(report.packages_per_sec(), 0.0);
+        assert!(report.to_string().ends_with(" 0 packages/sec"));
+    }
+
+    #[test]
+    fn test_per_package() {
+        let report = BenchReport {
+            packages: 4,
+            generate: Duration::ZERO,
+            classify: Duration::from_micros(10),
+            output: Duration::ZERO,
+        };
+        assert_eq!(
+            report.per_package(report.classify),
+            Duration::from_nanos(2_500)
+        );
+        assert_eq!(report.per_package(report.generate), Duration::ZERO);
+
+        // Counts past u32::MAX used to wrap, dividing by zero
+        let report = BenchReport {
+            packages: 1 << 32,
+            ..report
+        };
+        assert_eq!(
+            report.per_package(Duration::from_secs(1 << 32)),
+            Duration::from_secs(1)
+        );
+    }
+}
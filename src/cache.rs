@@ -0,0 +1,264 @@
+//! Least-recently-used cache of decisions for repeated identical
+//! measurements, e.g. the same SKU seen many times a day.
+//!
+//! Only exactly identical measurements under equal rules share a decision,
+//! so a cached decision is always the one `SortRules::decide` would make.
+//! Non-physical measurements (non-finite, or not positive) are never cached:
+//! they are sorted directly, so they are flagged as suspect as usual.
+
+use std::collections::HashMap;
+
+use crate::{Centimeters, Package, Shape, SortDecision, SortRules, TemperatureRange};
+
+/// Hit and miss counts of a cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Number of decisions currently cached
+    pub len: usize,
+}
+
+impl CacheStats {
+    /// Fraction of lookups answered from the cache (0 before any lookup)
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// Everything a decision depends on. Measurements are keyed by their exact
+/// bits; `rule_set` indexes the distinct rule sets seen by the cache.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    rule_set: usize,
+    dimensions: [u64; 3],
+    shape: Option<[u64; 3]>,
+    mass: u64,
+    temperature: Option<(u64, u64)>,
+    fragile: bool,
+    hazmat: bool,
+}
+
+/// Caches up to `capacity` decisions, evicting the least recently used
+#[derive(Debug, Clone)]
+pub struct DecisionCache {
+    capacity: usize,
+    entries: HashMap<CacheKey, (SortDecision, u64)>,
+    rule_sets: Vec<SortRules>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+impl DecisionCache {
+    /// Creates an empty cache holding at most `capacity` decisions
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::with_capacity(capacity),
+            rule_sets: Vec::new(),
+            clock: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Sorts the package under the rules, reusing a cached decision for the
+    /// same measurements and rules. Packages that can't be cached count as
+    /// misses.
+    pub fn decide(&mut self, rules: &SortRules, package: &Package) -> SortDecision {
+        self.clock += 1;
+        let Some(key) = self.key(rules, package) else {
+            self.stats.misses += 1;
+            return rules.decide(package);
+        };
+
+        if let Some((decision, last_used)) = self.entries.get_mut(&key) {
+            *last_used = self.clock;
+            self.stats.hits += 1;
+            return decision.clone();
+        }
+
+        self.stats.misses += 1;
+        let decision = rules.decide(package);
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                self.evict();
+            }
+            self.entries.insert(key, (decision.clone(), self.clock));
+        }
+        decision
+    }
+
+    /// Returns the hit and miss counts so far
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            len: self.entries.len(),
+            ..self.stats
+        }
+    }
+
+    /// Drops every cached decision
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.rule_sets.clear();
+    }
+
+    /// Builds the cache key, or `None` if the package or rules can't be
+    /// cached
+    fn key(&mut self, rules: &SortRules, package: &Package) -> Option<CacheKey> {
+        let rule_set = match self.rule_sets.iter().position(|known| known == rules) {
+            Some(index) => index,
+            None => {
+                self.rule_sets.push(rules.clone());
+                // Rules with a NaN threshold never equal themselves
+                if self.rule_sets.last() != Some(rules) {
+                    self.rule_sets.pop();
+                    return None;
+                }
+                self.rule_sets.len() - 1
+            }
+        };
+        measurements_key(rule_set, package)
+    }
+
+    fn evict(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, (_, last_used))| *last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.entries.remove(&key);
+        }
+    }
+}
+
+/// Keys the exact measurements, or `None` if any is non-physical
+fn measurements_key(rule_set: usize, package: &Package) -> Option<CacheKey> {
+    let physical = |d: Centimeters| d.value().is_finite() && d.value() > 0.0;
+    let bits = |d: Centimeters| physical(d).then(|| d.value().to_bits());
+
+    let dimensions = [
+        bits(package.width)?,
+        bits(package.height)?,
+        bits(package.length)?,
+    ];
+    let mass = package.mass.value();
+    if !(mass.is_finite() && mass >= 0.0) {
+        return None;
+    }
+    let shape = match package.shape {
+        Shape::Box => None,
+        Shape::Cylinder { diameter, length } => Some([bits(diameter)?, bits(length)?, 0]),
+        Shape::Irregular { bounding_box } => Some([
+            bits(bounding_box[0])?,
+            bits(bounding_box[1])?,
+            bits(bounding_box[2])?,
+        ]),
+    };
+
+    Some(CacheKey {
+        rule_set,
+        dimensions,
+        shape,
+        mass: mass.to_bits(),
+        temperature: package
+            .perishable
+            .map(|TemperatureRange { min, max }| (min.value().to_bits(), max.value().to_bits())),
+        fragile: package.fragile,
+        hazmat: package.hazmat,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Kilograms, SortCategory, SuspectMeasurement};
+
+    fn package(width: f64, height: f64, length: f64, mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(width),
+            Centimeters::new(height),
+            Centimeters::new(length),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_cache_hits() {
+        let rules = SortRules::default();
+        let mut cache = DecisionCache::new(10);
+
+        let first = cache.decide(&rules, &package(30.0, 20.0, 10.0, 2.0));
+        let second = cache.decide(&rules, &package(30.0, 20.0, 10.0, 2.0));
+        assert_eq!(first, second);
+        cache.decide(&rules, &package(30.0, 20.0, 10.0, 2.0).with_fragile());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (1, 2, 2));
+        assert!((stats.hit_rate() - 1.0 / 3.0).abs() < 1e-9);
+
+        // Different rules are a different entry, even under the same version
+        let strict = SortRules {
+            mass_threshold: Kilograms::new(1.0),
+            ..SortRules::default()
+        };
+        assert_eq!(strict.version(), rules.version());
+        let decision = cache.decide(&strict, &package(30.0, 20.0, 10.0, 2.0));
+        assert_eq!(decision.category, SortCategory::Special);
+        assert_eq!(cache.stats().misses, 3);
+    }
+
+    #[test]
+    fn test_cache_matches_direct_decisions() {
+        let rules = SortRules {
+            plausible_density: None,
+            ..SortRules::default()
+        };
+        let mut cache = DecisionCache::new(10);
+        for pkg in [
+            package(149.96, 50.0, 50.0, 10.0),
+            package(150.04, 50.0, 50.0, 10.0),
+            package(149.96, 50.0, 50.0, 10.0),
+            package(30.0, 20.0, 10.0, f64::NAN),
+            package(30.0, 20.0, 10.0, f64::NAN),
+            package(0.0, 20.0, 10.0, 1.0),
+        ] {
+            // Compared as text, since NaN weights never compare equal
+            assert_eq!(
+                format!("{:?}", cache.decide(&rules, &pkg)),
+                format!("{:?}", rules.decide(&pkg))
+            );
+        }
+        assert_eq!(
+            cache
+                .decide(&rules, &package(30.0, 20.0, 10.0, f64::NAN))
+                .suspect,
+            Some(SuspectMeasurement::NonPhysical)
+        );
+        // Only the two plausible packages were cached
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.len), (1, 2));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let rules = SortRules::default();
+        let mut cache = DecisionCache::new(2);
+        cache.decide(&rules, &package(10.0, 10.0, 10.0, 1.0));
+        cache.decide(&rules, &package(20.0, 20.0, 20.0, 1.0));
+        cache.decide(&rules, &package(10.0, 10.0, 10.0, 1.0));
+        cache.decide(&rules, &package(30.0, 30.0, 30.0, 1.0));
+        assert_eq!(cache.stats().len, 2);
+
+        // The 20 cm box was evicted, the 10 cm box was kept
+        cache.decide(&rules, &package(10.0, 10.0, 10.0, 1.0));
+        cache.decide(&rules, &package(20.0, 20.0, 20.0, 1.0));
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 4));
+    }
+}
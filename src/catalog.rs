@@ -0,0 +1,270 @@
+//! Known dimensions and mass by SKU, so catalog items can be sorted without
+//! a live measurement.
+//!
+//! A catalog file holds one `SKU, width, height, length, mass` line per item
+//! (cm and kg); `#` starts a comment.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fmt, fs, io};
+
+use crate::validate::validate;
+use crate::{Package, SortDecision, SortRules};
+
+/// Relative difference in volume or mass above which catalog and measured
+/// values are flagged as a discrepancy by default
+pub const DISCREPANCY_TOLERANCE: f64 = 0.1;
+
+/// Where the measurements a decision was made from came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Catalog,
+    Measured,
+}
+
+/// How far the measured package is from its catalog entry, as relative
+/// differences (`0.25` = measured is 25% larger)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Discrepancy {
+    pub volume: f64,
+    pub mass: f64,
+}
+
+/// A decision made with the help of the catalog
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogDecision {
+    pub decision: SortDecision,
+    pub source: Source,
+    /// Set when a measurement disagrees with the catalog beyond the tolerance
+    pub discrepancy: Option<Discrepancy>,
+}
+
+/// Known packages by SKU or barcode
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    items: HashMap<String, Package>,
+    /// Relative difference above which a measurement is flagged
+    pub tolerance: f64,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self {
+            items: HashMap::new(),
+            tolerance: DISCREPANCY_TOLERANCE,
+        }
+    }
+}
+
+impl Catalog {
+    /// Loads a catalog file
+    pub fn load(path: &Path) -> Result<Self, CatalogError> {
+        let contents = fs::read_to_string(path).map_err(|source| CatalogError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        contents.parse()
+    }
+
+    /// Adds or replaces an item
+    pub fn insert(&mut self, sku: impl Into<String>, package: Package) {
+        self.items.insert(sku.into(), package);
+    }
+
+    /// Looks up an item
+    pub fn get(&self, sku: &str) -> Option<&Package> {
+        self.items.get(sku)
+    }
+
+    /// Returns the number of items
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Checks if the catalog has no items
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Sorts a package by SKU.
+    ///
+    /// Known SKUs are sorted from their catalog entry; a measurement, if
+    /// given, is only compared against it, and a discrepancy beyond the
+    /// tolerance flags the decision for review. Unknown SKUs fall back to the
+    /// measurement.
+    pub fn decide(
+        &self,
+        rules: &SortRules,
+        sku: &str,
+        measured: Option<&Package>,
+    ) -> Result<CatalogDecision, CatalogError> {
+        let Some(known) = self.get(sku) else {
+            let measured = measured.ok_or_else(|| CatalogError::UnknownSku(sku.to_string()))?;
+            return Ok(CatalogDecision {
+                decision: rules.decide(measured),
+                source: Source::Measured,
+                discrepancy: None,
+            });
+        };
+
+        let mut decision = rules.decide(known);
+        let discrepancy = measured
+            .map(|measured| Discrepancy {
+                volume: measured.volume() / known.volume() - 1.0,
+                mass: measured.mass.value() / known.mass.value() - 1.0,
+            })
+            .filter(|d| d.volume.abs() > self.tolerance || d.mass.abs() > self.tolerance);
+        if discrepancy.is_some() {
+            decision.needs_review = true;
+        }
+
+        Ok(CatalogDecision {
+            decision,
+            source: Source::Catalog,
+            discrepancy,
+        })
+    }
+}
+
+impl FromStr for Catalog {
+    type Err = CatalogError;
+
+    /// Parses the catalog file format
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut catalog = Catalog::default();
+        for (index, line) in s.lines().enumerate() {
+            let text = line.split('#').next().unwrap_or_default().trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            let syntax = || CatalogError::Syntax {
+                line: index + 1,
+                text: line.to_string(),
+            };
+            let fields: Vec<&str> = text.split(',').map(str::trim).collect();
+            let [sku, width, height, length, mass] = fields.as_slice() else {
+                return Err(syntax());
+            };
+            let number = |field: &str| field.parse::<f64>().map_err(|_| syntax());
+            let package = validate(
+                number(width)?,
+                number(height)?,
+                number(length)?,
+                number(mass)?,
+            )
+            .map_err(|_| syntax())?;
+            catalog.insert(*sku, package);
+        }
+        Ok(catalog)
+    }
+}
+
+/// Error returned when loading a catalog or sorting by SKU fails
+#[derive(Debug)]
+pub enum CatalogError {
+    /// The catalog file couldn't be read
+    Io { path: PathBuf, source: io::Error },
+    /// A catalog line isn't of the form `SKU, width, height, length, mass`
+    /// with valid measurements
+    Syntax { line: usize, text: String },
+    /// The SKU isn't in the catalog and no measurement was given
+    UnknownSku(String),
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Io { path, source } => {
+                write!(f, "failed to read {}: {}", path.display(), source)
+            }
+            CatalogError::Syntax { line, text } => write!(
+                f,
+                "line {}: expected SKU, width, height, length, mass, got {:?}",
+                line, text
+            ),
+            CatalogError::UnknownSku(sku) => {
+                write!(f, "unknown SKU {:?} and no measurement", sku)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CatalogError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Centimeters, Kilograms, SortCategory};
+
+    fn package(width: f64, height: f64, length: f64, mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(width),
+            Centimeters::new(height),
+            Centimeters::new(length),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_parse_catalog() {
+        let catalog: Catalog = "# sku, w, h, l, kg\n\
+                                KETTLE-01, 25, 20, 30, 1.8\n\
+                                \n\
+                                DESK-07, 160, 15, 80, 32 # flat-packed\n"
+            .parse()
+            .unwrap();
+        assert_eq!(catalog.len(), 2);
+        assert_eq!(
+            catalog.get("DESK-07"),
+            Some(&package(160.0, 15.0, 80.0, 32.0))
+        );
+
+        assert!(matches!(
+            "KETTLE-01, 25, 20, 30".parse::<Catalog>(),
+            Err(CatalogError::Syntax { line: 1, .. })
+        ));
+        assert!("KETTLE-01, 25, 20, -30, 1.8".parse::<Catalog>().is_err());
+    }
+
+    #[test]
+    fn test_catalog_decide() {
+        let rules = SortRules::default();
+        let mut catalog = Catalog::default();
+        catalog.insert("DESK-07", package(160.0, 15.0, 80.0, 32.0));
+
+        // Known SKU, no measurement
+        let result = catalog.decide(&rules, "DESK-07", None).unwrap();
+        assert_eq!(result.source, Source::Catalog);
+        assert_eq!(result.decision.category, SortCategory::Rejected);
+
+        // Measurement within tolerance
+        let measured = package(161.0, 15.0, 80.0, 31.0);
+        let result = catalog.decide(&rules, "DESK-07", Some(&measured)).unwrap();
+        assert!(result.discrepancy.is_none());
+        assert!(!result.decision.needs_review);
+
+        // Measurement far from the catalog
+        let measured = package(160.0, 15.0, 80.0, 12.0);
+        let result = catalog.decide(&rules, "DESK-07", Some(&measured)).unwrap();
+        assert!((result.discrepancy.unwrap().mass + 0.625).abs() < 1e-9);
+        assert!(result.decision.needs_review);
+
+        // Unknown SKU falls back to the measurement
+        let result = catalog.decide(&rules, "LAMP-02", Some(&measured)).unwrap();
+        assert_eq!(result.source, Source::Measured);
+        assert_eq!(result.decision.category, SortCategory::Special);
+        assert!(matches!(
+            catalog.decide(&rules, "LAMP-02", None),
+            Err(CatalogError::UnknownSku(_))
+        ));
+    }
+}
@@ -0,0 +1,365 @@
+//! Column mappings for third-party record layouts: which column holds each
+//! measurement, and in what unit.
+//!
+//! A mapping is written `FIELD=COLUMN` or `FIELD=COLUMN:UNIT`, e.g.
+//! `mass=WEIGHT_LB:lb`. Dimensions default to cm and mass to kg; unmapped
+//! fields are read from the column of the same name.
+//!
+//! [`ColumnMap::detect`] also recognizes units in headers: a `width_in` or
+//! `mass_lb` column is read in inches or pounds without a mapping.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::numbers::{parse_number, ParseMode};
+use crate::units::{CM_PER_INCH, KG_PER_POUND};
+use crate::validate::{check, validate, FieldError, FieldErrorKind, FIELDS};
+use crate::Package;
+
+/// Unit a column is recorded in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Millimeters,
+    Centimeters,
+    Meters,
+    Inches,
+    Grams,
+    Kilograms,
+    Pounds,
+}
+
+impl Unit {
+    pub const ALL: [Unit; 7] = [
+        Unit::Millimeters,
+        Unit::Centimeters,
+        Unit::Meters,
+        Unit::Inches,
+        Unit::Grams,
+        Unit::Kilograms,
+        Unit::Pounds,
+    ];
+
+    /// Returns the unit's symbol
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Millimeters => "mm",
+            Unit::Centimeters => "cm",
+            Unit::Meters => "m",
+            Unit::Inches => "in",
+            Unit::Grams => "g",
+            Unit::Kilograms => "kg",
+            Unit::Pounds => "lb",
+        }
+    }
+
+    /// Checks if the unit measures length rather than mass
+    pub fn is_length(&self) -> bool {
+        matches!(
+            self,
+            Unit::Millimeters | Unit::Centimeters | Unit::Meters | Unit::Inches
+        )
+    }
+
+    /// Converts a value to centimeters (lengths) or kilograms (masses)
+    pub fn to_base(&self, value: f64) -> f64 {
+        match self {
+            Unit::Millimeters => value / 10.0,
+            Unit::Centimeters | Unit::Kilograms => value,
+            Unit::Meters => value * 100.0,
+            Unit::Inches => value * CM_PER_INCH,
+            Unit::Grams => value / 1_000.0,
+            Unit::Pounds => value * KG_PER_POUND,
+        }
+    }
+
+    fn default_for(field: &str) -> Self {
+        if field == "mass" {
+            Unit::Kilograms
+        } else {
+            Unit::Centimeters
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Where one measurement is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMapping {
+    /// One of [`FIELDS`]
+    pub field: String,
+    pub column: String,
+    pub unit: Unit,
+}
+
+impl FromStr for ColumnMapping {
+    type Err = ParseColumnMapError;
+
+    /// Parses `FIELD=COLUMN` or `FIELD=COLUMN:UNIT`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseColumnMapError(s.to_string());
+        let (field, target) = s.split_once('=').ok_or_else(error)?;
+        let field = FIELDS
+            .into_iter()
+            .find(|f| f.eq_ignore_ascii_case(field.trim()))
+            .ok_or_else(error)?;
+        let (column, unit) = match target.rsplit_once(':') {
+            Some((column, unit)) => {
+                let unit = Unit::ALL
+                    .into_iter()
+                    .find(|u| u.as_str().eq_ignore_ascii_case(unit.trim()))
+                    .ok_or_else(error)?;
+                (column, unit)
+            }
+            None => (target, Unit::default_for(field)),
+        };
+        let column = column.trim();
+        if column.is_empty() || unit.is_length() != (field != "mass") {
+            return Err(error());
+        }
+
+        Ok(Self {
+            field: field.to_string(),
+            column: column.to_string(),
+            unit,
+        })
+    }
+}
+
+impl fmt::Display for ColumnMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}:{}", self.field, self.column, self.unit)
+    }
+}
+
+/// Column mappings by field
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnMap {
+    mappings: BTreeMap<String, ColumnMapping>,
+}
+
+impl ColumnMap {
+    /// Adds a mapping, replacing any earlier one for the same field
+    pub fn set(&mut self, mapping: ColumnMapping) {
+        self.mappings.insert(mapping.field.clone(), mapping);
+    }
+
+    /// Returns the mappings, ordered by field
+    pub fn mappings(&self) -> impl Iterator<Item = &ColumnMapping> {
+        self.mappings.values()
+    }
+
+    /// Resolves the columns of a file from its header. Mapped fields keep
+    /// their mapping; any other field is read from a column named after it,
+    /// either plainly (`width`, in cm or kg) or with a unit suffix
+    /// (`width_in`, `mass_lb`). Names are matched ignoring case.
+    ///
+    /// Fails if a field could be read from more than one column, e.g. a file
+    /// with both `mass_kg` and `mass_lb`. Fields with no matching column are
+    /// left unmapped and reported missing row by row.
+    pub fn detect(&self, header: &[&str]) -> Result<ColumnMap, AmbiguousColumnsError> {
+        let mut detected = self.clone();
+        for field in FIELDS {
+            if self.mappings.contains_key(field) {
+                continue;
+            }
+            let candidates: Vec<ColumnMapping> = header
+                .iter()
+                .map(|column| column.trim())
+                .filter_map(|column| {
+                    let unit = header_unit(field, column)?;
+                    Some(ColumnMapping {
+                        field: field.to_string(),
+                        column: column.to_string(),
+                        unit,
+                    })
+                })
+                .collect();
+            match <[ColumnMapping; 1]>::try_from(candidates) {
+                Ok([mapping]) => detected.set(mapping),
+                Err(candidates) if candidates.is_empty() => {}
+                Err(candidates) => {
+                    return Err(AmbiguousColumnsError {
+                        field: field.to_string(),
+                        columns: candidates.into_iter().map(|m| m.column).collect(),
+                    })
+                }
+            }
+        }
+        Ok(detected)
+    }
+
+    /// Builds a package from a row, reading each measurement from its mapped
+    /// column and converting it to cm or kg. Every bad field is reported,
+    /// keyed by field name.
+    pub fn package_from_row(
+        &self,
+        header: &[&str],
+        row: &[&str],
+        mode: ParseMode,
+    ) -> Result<Package, Vec<FieldError>> {
+        let mut values = [0.0; 4];
+        let mut errors = Vec::new();
+
+        for (index, field) in FIELDS.into_iter().enumerate() {
+            let (column, unit) = match self.mappings.get(field) {
+                Some(mapping) => (mapping.column.as_str(), mapping.unit),
+                None => (field, Unit::default_for(field)),
+            };
+            let value = header
+                .iter()
+                .position(|h| h.trim() == column)
+                .and_then(|i| row.get(i))
+                .ok_or(FieldErrorKind::Missing)
+                .and_then(|text| parse_number(text, mode).map_err(FieldErrorKind::Invalid))
+                .map(|parsed| unit.to_base(parsed.value))
+                .and_then(|value| check(field, value).map(|()| value));
+
+            match value {
+                Ok(value) => values[index] = value,
+                Err(kind) => errors.push(FieldError {
+                    field: field.to_string(),
+                    kind,
+                }),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let [width, height, length, mass] = values;
+        validate(width, height, length, mass)
+    }
+}
+
+/// Returns the unit of a header column that names the field, plainly or with
+/// a unit suffix of the right kind
+fn header_unit(field: &str, column: &str) -> Option<Unit> {
+    let lower = column.to_ascii_lowercase();
+    let suffix = lower.strip_prefix(field)?;
+    if suffix.is_empty() {
+        return Some(Unit::default_for(field));
+    }
+    let symbol = suffix.strip_prefix('_')?;
+    Unit::ALL
+        .into_iter()
+        .find(|unit| unit.as_str() == symbol && unit.is_length() == (field != "mass"))
+}
+
+/// Error returned when a header has more than one column a field could be
+/// read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousColumnsError {
+    pub field: String,
+    pub columns: Vec<String>,
+}
+
+impl fmt::Display for AmbiguousColumnsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ambiguous columns for {}: {}",
+            self.field,
+            self.columns.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for AmbiguousColumnsError {}
+
+/// Error returned for a mapping not of the form `FIELD=COLUMN[:UNIT]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColumnMapError(String);
+
+impl fmt::Display for ParseColumnMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid column mapping {:?}: expected FIELD=COLUMN[:UNIT]",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseColumnMapError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Centimeters;
+
+    #[test]
+    fn test_parse_mapping() {
+        let mapping: ColumnMapping = "mass=WEIGHT_LB:lb".parse().unwrap();
+        assert_eq!(mapping.column, "WEIGHT_LB");
+        assert_eq!(mapping.unit, Unit::Pounds);
+        assert_eq!(mapping.to_string(), "mass=WEIGHT_LB:lb");
+
+        let mapping: ColumnMapping = "width=W_CM".parse().unwrap();
+        assert_eq!(mapping.unit, Unit::Centimeters);
+
+        assert!("depth=D".parse::<ColumnMapping>().is_err());
+        assert!("width=W:kg".parse::<ColumnMapping>().is_err());
+        assert!("mass=".parse::<ColumnMapping>().is_err());
+    }
+
+    #[test]
+    fn test_package_from_row() {
+        let mut map = ColumnMap::default();
+        map.set("width=W_MM:mm".parse().unwrap());
+        map.set("length=L_IN:in".parse().unwrap());
+        map.set("mass=WEIGHT_LB:lb".parse().unwrap());
+
+        let header = ["SKU", "W_MM", "height", "L_IN", "WEIGHT_LB"];
+        let package = map
+            .package_from_row(&header, &["A1", "300", "20", "10", "10"], ParseMode::Strict)
+            .unwrap();
+        assert_eq!(package.width, Centimeters::new(30.0));
+        assert_eq!(package.length, Centimeters::new(25.4));
+        assert!((package.mass.value() - 4.535_923_7).abs() < 1e-9);
+
+        let errors = map
+            .package_from_row(&header[..4], &["A1", "-3", "x", "10"], ParseMode::Strict)
+            .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["width", "height", "mass"]);
+        assert_eq!(errors[2].kind, FieldErrorKind::Missing);
+    }
+
+    #[test]
+    fn test_detect_header_units() {
+        let header = ["SKU", "Width_In", "height_in", "length", "mass_lb"];
+        let map = ColumnMap::default().detect(&header).unwrap();
+        let package = map
+            .package_from_row(&header, &["A1", "10", "10", "30", "10"], ParseMode::Strict)
+            .unwrap();
+        assert_eq!(package.width, Centimeters::new(25.4));
+        assert_eq!(package.length, Centimeters::new(30.0));
+        assert!((package.mass.value() - 4.535_923_7).abs() < 1e-9);
+
+        // An explicit mapping wins over detection
+        let mut explicit = ColumnMap::default();
+        explicit.set("mass=SKU:kg".parse().unwrap());
+        let map = explicit.detect(&header).unwrap();
+        assert_eq!(
+            map.mappings().find(|m| m.field == "mass").unwrap().column,
+            "SKU"
+        );
+
+        // Units of the wrong kind aren't detected, and two candidates fail
+        let map = ColumnMap::default().detect(&["width_kg"]).unwrap();
+        assert_eq!(map.mappings().count(), 0);
+        assert_eq!(
+            ColumnMap::default()
+                .detect(&["width", "mass_kg", "mass_lb"])
+                .unwrap_err()
+                .to_string(),
+            "ambiguous columns for mass: mass_kg, mass_lb"
+        );
+    }
+}
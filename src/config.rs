@@ -0,0 +1,871 @@
+//! Layered configuration.
+//!
+//! Settings are resolved from, lowest to highest precedence:
+//! 1. built-in defaults
+//! 2. a config file (`--config <path>` or `TPS_CONFIG`)
+//! 3. `TPS_*` environment variables, e.g. `TPS_MASS_THRESHOLD=25`;
+//!    variables that don't name a setting are ignored
+//! 4. command-line flags, e.g. `--mass-threshold 25`
+//!
+//! The config file holds one `key = value` setting per line; blank lines and
+//! lines starting with `#` are ignored. Keys are the same everywhere, written
+//! in upper case for environment variables and kebab-case for flags.
+//!
+//! The file may also define named rule profiles. A `[profile NAME]` line
+//! starts a profile; the rule settings after it override the base rules when
+//! that profile is selected (with the `profile` setting, or per request with
+//! [`Config::rules_for`]):
+//!
+//! ```text
+//! mass_threshold = 20
+//!
+//! [profile freight]
+//! mass_threshold = 40
+//! ```
+//!
+//! An `[override NAME]` section schedules rule changes for a UTC date range
+//! and/or time of day (see [`crate::schedule`]). Overrides apply on top of
+//! whichever profile is selected:
+//!
+//! ```text
+//! [override peak-season]
+//! dates = 2024-11-25..2024-12-24
+//! hours = 06:00..22:00
+//! mass_threshold = 18
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::columns::ColumnMap;
+use crate::i18n::Locale;
+use crate::labels::CategoryLabels;
+use crate::numbers::NumberFormat;
+use crate::record::OutputFormat;
+use crate::schedule::{RuleOverride, Schedule};
+use crate::uncertainty::Uncertainty;
+use crate::units::UnitSystem;
+use crate::{Aperture, AxisLimits, Centimeters, Kilograms, SortCategory, SortRules};
+
+/// Prefix of the environment variables read as configuration
+pub const ENV_PREFIX: &str = "TPS_";
+
+/// A single `(key, value)` setting
+pub type Setting = (String, String);
+
+/// The fully resolved configuration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    /// The base rules, used when no profile is selected
+    pub rules: SortRules,
+    pub locale: Locale,
+    pub labels: CategoryLabels,
+    /// Units measurements are rendered in
+    pub units: UnitSystem,
+    /// How the CLI writes decisions
+    pub output: OutputFormat,
+    /// How numbers are parsed and rendered (`None` follows the locale)
+    pub number_format: Option<NumberFormat>,
+    /// Columns measurements are read from in third-party records
+    pub columns: ColumnMap,
+    /// How far measurements may be off; packages whose category could differ
+    /// within it are queued for review as near-limit
+    pub uncertainty: Uncertainty,
+    /// The selected rule profile
+    pub profile: Option<String>,
+    /// Rule overrides of each named profile
+    pub profiles: BTreeMap<String, Vec<Setting>>,
+}
+
+impl Config {
+    /// Resolves the configuration from every layer.
+    ///
+    /// # Arguments
+    /// * `args` - Command-line arguments, without the program name
+    /// * `vars` - Environment variables
+    ///
+    /// # Returns
+    /// * The configuration and the remaining positional arguments (the
+    ///   subcommand)
+    pub fn load(
+        args: &[String],
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<(Config, Vec<String>), ConfigError> {
+        let (flags, positional) = parse_args(args)?;
+        let vars: Vec<Setting> = vars
+            .into_iter()
+            .filter_map(|(name, value)| Some((env_key(&name)?, value)))
+            .collect();
+
+        let path = vars
+            .iter()
+            .chain(flags.iter())
+            .rev()
+            .find(|(key, _)| key == "config")
+            .map(|(_, value)| PathBuf::from(value));
+
+        let mut config = Config::default();
+        if let Some(path) = path {
+            config.apply_file(&path)?;
+        }
+        // Other programs' `TPS_*` variables aren't ours to reject; unknown
+        // keys are only an error when given as flags
+        for (key, value) in vars.iter().filter(|(key, _)| key != "config") {
+            match config.set(key, value) {
+                Ok(()) | Err(ConfigError::UnknownKey(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        for (key, value) in &flags {
+            if key != "config" {
+                config.set(key, value)?;
+            }
+        }
+        config.active_rules()?;
+
+        Ok((config, positional))
+    }
+
+    /// Returns the configured number format, or the locale's
+    pub fn number_format(&self) -> NumberFormat {
+        self.number_format
+            .unwrap_or_else(|| self.locale.number_format())
+    }
+
+    /// Returns the name the CLI prints for a category: its remapped label if
+    /// one is configured, otherwise its name in the configured locale
+    pub fn category_label(&self, category: SortCategory) -> &str {
+        self.labels
+            .overrides(category)
+            .unwrap_or_else(|| self.locale.category(category))
+    }
+
+    /// Returns the rules of the selected profile, or the base rules if none
+    /// is selected
+    pub fn active_rules(&self) -> Result<SortRules, ConfigError> {
+        self.rules_for(self.profile.as_deref())
+    }
+
+    /// Returns the rules of a named profile (the base rules for `None`), so a
+    /// profile can be chosen per request or batch
+    pub fn rules_for(&self, profile: Option<&str>) -> Result<SortRules, ConfigError> {
+        let mut rules = self.rules.clone();
+        if let Some(name) = profile {
+            let settings = self
+                .profiles
+                .get(name)
+                .ok_or_else(|| ConfigError::UnknownProfile(name.to_string()))?;
+            for (key, value) in settings {
+                set_rule(&mut rules, key, value)?;
+            }
+            rules.profile = Some(name.to_string());
+        }
+        Ok(rules)
+    }
+
+    /// Applies the settings in a config file
+    pub fn apply_file(&mut self, path: &Path) -> Result<(), ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.apply_str(&contents)
+    }
+
+    /// Applies settings written in the config file format
+    pub fn apply_str(&mut self, contents: &str) -> Result<(), ConfigError> {
+        enum Section {
+            Base,
+            Profile(String),
+            Override(usize),
+        }
+        let mut section = Section::Base;
+
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            let syntax = || ConfigError::Syntax {
+                line: index + 1,
+                text: line.to_string(),
+            };
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let (kind, name) = header
+                    .trim()
+                    .split_once(' ')
+                    .map(|(kind, name)| (kind, name.trim()))
+                    .filter(|(_, name)| !name.is_empty())
+                    .ok_or_else(syntax)?;
+                section = match kind {
+                    "profile" => {
+                        self.profiles.entry(name.to_string()).or_default();
+                        Section::Profile(name.to_string())
+                    }
+                    "override" => {
+                        self.rules.schedule.push(RuleOverride {
+                            name: name.to_string(),
+                            ..RuleOverride::default()
+                        });
+                        Section::Override(self.rules.schedule.len() - 1)
+                    }
+                    _ => return Err(syntax()),
+                };
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(syntax)?;
+            let (key, value) = (key.trim(), value.trim());
+            match &section {
+                Section::Base => self.set(key, value)?,
+                Section::Profile(profile) => {
+                    // Validate now so mistakes surface when the file is read
+                    set_rule(&mut SortRules::default(), key, value)?;
+                    self.profiles
+                        .entry(profile.clone())
+                        .or_default()
+                        .push((key.to_string(), value.to_string()));
+                }
+                Section::Override(index) => {
+                    set_override(&mut self.rules.schedule[*index], key, value)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies a single setting
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        match key {
+            "locale" => {
+                self.locale = Locale::from_tag(value)
+                    .ok_or_else(|| invalid(key, value, "unsupported locale"))?
+            }
+            "labels" => {
+                self.labels = value
+                    .parse()
+                    .map_err(|err| invalid(key, value, &format!("{}", err)))?
+            }
+            "units" => {
+                self.units = value
+                    .parse()
+                    .map_err(|err| invalid(key, value, &format!("{}", err)))?
+            }
+            "output" => {
+                self.output = value
+                    .parse()
+                    .map_err(|err| invalid(key, value, &format!("{}", err)))?
+            }
+            "number_format" => {
+                self.number_format = if value.eq_ignore_ascii_case("locale") {
+                    None
+                } else {
+                    Some(
+                        value
+                            .parse()
+                            .map_err(|err| invalid(key, value, &format!("{}", err)))?,
+                    )
+                }
+            }
+            "map" => self.columns.set(
+                value
+                    .parse()
+                    .map_err(|err| invalid(key, value, &format!("{}", err)))?,
+            ),
+            "dimension_uncertainty" => {
+                self.uncertainty.dimension = Centimeters::new(non_negative(key, value)?)
+            }
+            "mass_uncertainty" => self.uncertainty.mass = Kilograms::new(non_negative(key, value)?),
+            "profile" => {
+                self.profile = match value {
+                    "" | "none" => None,
+                    name => Some(name.to_string()),
+                }
+            }
+            _ => set_rule(&mut self.rules, key, value)?,
+        }
+        Ok(())
+    }
+}
+
+/// Parses a setting that must be a finite number of at least zero
+fn non_negative(key: &str, value: &str) -> Result<f64, ConfigError> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+        .ok_or_else(|| invalid(key, value, "expected a non-negative number"))
+}
+
+/// Applies a single rule setting to a rule set
+fn set_rule(rules: &mut SortRules, key: &str, value: &str) -> Result<(), ConfigError> {
+    let number = || {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite() && *n > 0.0)
+            .ok_or_else(|| invalid(key, value, "expected a positive number"))
+    };
+
+    match key {
+        "volume_threshold" => rules.volume_threshold = number()?,
+        "dimension_threshold" => rules.dimension_threshold = Centimeters::new(number()?),
+        "mass_threshold" => rules.mass_threshold = Kilograms::new(number()?),
+        "axis_limits" => {
+            rules.axis_limits = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                let limits: Vec<f64> = value
+                    .split('x')
+                    .map(|limit| limit.trim().parse().ok().filter(|&l: &f64| l > 0.0))
+                    .collect::<Option<_>>()
+                    .filter(|limits: &Vec<f64>| limits.len() == 3)
+                    .ok_or_else(|| {
+                        invalid(key, value, "expected LENGTHxWIDTHxHEIGHT in cm, or none")
+                    })?;
+                Some(AxisLimits::new(
+                    Centimeters::new(limits[0]),
+                    Centimeters::new(limits[1]),
+                    Centimeters::new(limits[2]),
+                ))
+            }
+        }
+        "max_mass" => {
+            rules.max_mass = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                Some(Kilograms::new(number()?))
+            }
+        }
+        "aperture" => {
+            rules.aperture = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                let (width, height) = value
+                    .split_once('x')
+                    .and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)))
+                    .filter(|&(w, h): &(f64, f64)| w > 0.0 && h > 0.0)
+                    .ok_or_else(|| invalid(key, value, "expected WIDTHxHEIGHT in cm, or none"))?;
+                Some(Aperture::new(
+                    Centimeters::new(width),
+                    Centimeters::new(height),
+                ))
+            }
+        }
+        "min_dimension" => {
+            rules.min_dimension = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                Some(Centimeters::new(number()?))
+            }
+        }
+        "plausible_density" => {
+            rules.plausible_density = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                let bounds = value
+                    .split_once("..")
+                    .and_then(|(min, max)| {
+                        Some((min.trim().parse().ok()?, max.trim().parse().ok()?))
+                    })
+                    .filter(|&(min, max): &(f64, f64)| 0.0 <= min && min < max)
+                    .ok_or_else(|| invalid(key, value, "expected MIN..MAX in kg/m³, or none"))?;
+                Some(bounds)
+            }
+        }
+        "dim_divisor" => rules.dim_divisor = number()?,
+        "dense_threshold" => {
+            rules.dense_threshold = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                Some(number()?)
+            }
+        }
+        "light_threshold" => {
+            rules.light_threshold = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                Some(number()?)
+            }
+        }
+        _ => return Err(ConfigError::UnknownKey(key.to_string())),
+    }
+    Ok(())
+}
+
+/// Applies a single setting to a scheduled override
+fn set_override(
+    rule_override: &mut RuleOverride,
+    key: &str,
+    value: &str,
+) -> Result<(), ConfigError> {
+    fn range<T: std::str::FromStr>(value: &str) -> Option<(T, T)> {
+        let (start, end) = value.split_once("..")?;
+        Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+    }
+
+    match key {
+        "dates" => {
+            let dates = range(value)
+                .filter(|(start, end)| start <= end)
+                .ok_or_else(|| invalid(key, value, "expected YYYY-MM-DD..YYYY-MM-DD"))?;
+            rule_override.schedule.dates = Some(dates);
+        }
+        "hours" => {
+            let hours = range(value).ok_or_else(|| invalid(key, value, "expected HH:MM..HH:MM"))?;
+            rule_override.schedule.hours = Some(hours);
+        }
+        _ => {
+            let mut rules = SortRules::default();
+            set_rule(&mut rules, key, value)?;
+            match key {
+                "volume_threshold" => rule_override.volume_threshold = Some(rules.volume_threshold),
+                "dimension_threshold" => {
+                    rule_override.dimension_threshold = Some(rules.dimension_threshold)
+                }
+                "mass_threshold" => rule_override.mass_threshold = Some(rules.mass_threshold),
+                _ => return Err(ConfigError::UnknownKey(key.to_string())),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Builds a [`ConfigError::InvalidValue`]
+fn invalid(key: &str, value: &str, reason: &str) -> ConfigError {
+    ConfigError::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+impl fmt::Display for Config {
+    /// Formats the configuration in the config file format
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "volume_threshold = {}", self.rules.volume_threshold)?;
+        writeln!(
+            f,
+            "dimension_threshold = {}",
+            self.rules.dimension_threshold.value()
+        )?;
+        match self.rules.axis_limits {
+            Some(limits) => writeln!(
+                f,
+                "axis_limits = {}x{}x{}",
+                limits.length.value(),
+                limits.width.value(),
+                limits.height.value()
+            )?,
+            None => writeln!(f, "axis_limits = none")?,
+        }
+        writeln!(f, "mass_threshold = {}", self.rules.mass_threshold.value())?;
+        match self.rules.max_mass {
+            Some(max) => writeln!(f, "max_mass = {}", max.value())?,
+            None => writeln!(f, "max_mass = none")?,
+        }
+        match self.rules.aperture {
+            Some(aperture) => writeln!(
+                f,
+                "aperture = {}x{}",
+                aperture.width.value(),
+                aperture.height.value()
+            )?,
+            None => writeln!(f, "aperture = none")?,
+        }
+        match self.rules.min_dimension {
+            Some(min) => writeln!(f, "min_dimension = {}", min.value())?,
+            None => writeln!(f, "min_dimension = none")?,
+        }
+        match self.rules.plausible_density {
+            Some((min, max)) => writeln!(f, "plausible_density = {}..{}", min, max)?,
+            None => writeln!(f, "plausible_density = none")?,
+        }
+        writeln!(f, "dim_divisor = {}", self.rules.dim_divisor)?;
+        match self.rules.dense_threshold {
+            Some(threshold) => writeln!(f, "dense_threshold = {}", threshold)?,
+            None => writeln!(f, "dense_threshold = none")?,
+        }
+        match self.rules.light_threshold {
+            Some(threshold) => writeln!(f, "light_threshold = {}", threshold)?,
+            None => writeln!(f, "light_threshold = none")?,
+        }
+        writeln!(f, "locale = {}", self.locale.tag())?;
+        writeln!(f, "labels = {}", self.labels)?;
+        writeln!(f, "units = {}", self.units)?;
+        writeln!(f, "output = {}", self.output)?;
+        if let Some(format) = self.number_format {
+            writeln!(f, "number_format = {}", format)?;
+        }
+        writeln!(
+            f,
+            "dimension_uncertainty = {}",
+            self.uncertainty.dimension.value()
+        )?;
+        writeln!(f, "mass_uncertainty = {}", self.uncertainty.mass.value())?;
+        for mapping in self.columns.mappings() {
+            writeln!(f, "map = {}", mapping)?;
+        }
+        if let Some(profile) = &self.profile {
+            writeln!(f, "profile = {}", profile)?;
+        }
+        for (name, settings) in &self.profiles {
+            writeln!(f, "\n[profile {}]", name)?;
+            for (key, value) in settings {
+                writeln!(f, "{} = {}", key, value)?;
+            }
+        }
+        for rule_override in &self.rules.schedule {
+            write_override(f, rule_override)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes an `[override NAME]` section
+fn write_override(f: &mut fmt::Formatter<'_>, rule_override: &RuleOverride) -> fmt::Result {
+    let Schedule { dates, hours } = rule_override.schedule;
+    writeln!(f, "\n[override {}]", rule_override.name)?;
+    if let Some((start, end)) = dates {
+        writeln!(f, "dates = {}..{}", start, end)?;
+    }
+    if let Some((start, end)) = hours {
+        writeln!(f, "hours = {}..{}", start, end)?;
+    }
+    if let Some(volume) = rule_override.volume_threshold {
+        writeln!(f, "volume_threshold = {}", volume)?;
+    }
+    if let Some(dimension) = rule_override.dimension_threshold {
+        writeln!(f, "dimension_threshold = {}", dimension.value())?;
+    }
+    if let Some(mass) = rule_override.mass_threshold {
+        writeln!(f, "mass_threshold = {}", mass.value())?;
+    }
+    Ok(())
+}
+
+/// Maps a `TPS_*` environment variable name to a config key
+fn env_key(name: &str) -> Option<String> {
+    name.strip_prefix(ENV_PREFIX).map(str::to_ascii_lowercase)
+}
+
+/// Splits command-line arguments into `--key value` / `--key=value` flags
+/// (as config keys) and positional arguments
+fn parse_args(args: &[String]) -> Result<(Vec<Setting>, Vec<String>), ConfigError> {
+    let mut flags = Vec::new();
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            positional.push(arg.clone());
+            continue;
+        };
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name, value.to_string()),
+            None => (
+                flag,
+                args.next()
+                    .ok_or_else(|| ConfigError::MissingValue(flag.to_string()))?
+                    .clone(),
+            ),
+        };
+        flags.push((name.replace('-', "_"), value));
+    }
+
+    Ok((flags, positional))
+}
+
+/// Error returned when the configuration can't be resolved
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file couldn't be read
+    Io { path: PathBuf, source: io::Error },
+    /// A config file line isn't of the form `key = value`
+    Syntax { line: usize, text: String },
+    /// A setting names an unknown key
+    UnknownKey(String),
+    /// A profile was selected that the configuration doesn't define
+    UnknownProfile(String),
+    /// A command-line flag was given without a value
+    MissingValue(String),
+    /// A setting has a value that can't be used
+    InvalidValue {
+        key: String,
+        value: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "failed to read {}: {}", path.display(), source)
+            }
+            ConfigError::Syntax { line, text } => {
+                write!(f, "line {}: expected key = value, got {:?}", line, text)
+            }
+            ConfigError::UnknownKey(key) => write!(f, "unknown setting: {}", key),
+            ConfigError::UnknownProfile(name) => write!(f, "unknown profile: {}", name),
+            ConfigError::MissingValue(flag) => write!(f, "missing value for --{}", flag),
+            ConfigError::InvalidValue { key, value, reason } => {
+                write!(f, "invalid {} {:?}: {}", key, value, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortCategory;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn vars(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_layer_precedence() {
+        let path = std::env::temp_dir().join(format!("tps-config-{}.conf", std::process::id()));
+        fs::write(
+            &path,
+            "# site config\nmass_threshold = 22\ndimension_threshold = 140\nlocale = fr\n",
+        )
+        .unwrap();
+
+        let args = strings(&[
+            "--config",
+            path.to_str().unwrap(),
+            "--mass-threshold=30",
+            "config",
+            "show",
+        ]);
+        let env = vars(&[
+            ("TPS_MASS_THRESHOLD", "25"),
+            ("TPS_LOCALE", "de"),
+            ("HOME", "/root"),
+        ]);
+        let (config, command) = Config::load(&args, env).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Flag beats env beats file beats default
+        assert_eq!(config.rules.mass_threshold, Kilograms::new(30.0));
+        assert_eq!(config.locale, Locale::German);
+        assert_eq!(config.rules.dimension_threshold, Centimeters::new(140.0));
+        assert_eq!(config.rules.volume_threshold, 1_000_000.0);
+        assert_eq!(command, strings(&["config", "show"]));
+    }
+
+    #[test]
+    fn test_config_flag_beats_env() {
+        let dir = std::env::temp_dir();
+        let prod = dir.join(format!("tps-prod-{}.conf", std::process::id()));
+        let candidate = dir.join(format!("tps-candidate-{}.conf", std::process::id()));
+        fs::write(&prod, "mass_threshold = 20\n").unwrap();
+        fs::write(&candidate, "mass_threshold = 25\n").unwrap();
+
+        let args = strings(&["--config", candidate.to_str().unwrap()]);
+        let env = vars(&[("TPS_CONFIG", prod.to_str().unwrap()), ("TPS_FOO", "1")]);
+        let loaded = Config::load(&args, env);
+        fs::remove_file(&prod).unwrap();
+        fs::remove_file(&candidate).unwrap();
+
+        // The flag's file is used, and the unrelated variable is ignored
+        let (config, _) = loaded.unwrap();
+        assert_eq!(config.rules.mass_threshold, Kilograms::new(25.0));
+        assert!(matches!(
+            Config::load(&strings(&["--foo", "1"]), Vec::new()),
+            Err(ConfigError::UnknownKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let mut config = Config::default();
+        config
+            .apply_str(
+                "aperture = 140x120\n\
+                 axis_limits = 200x150x150\n\
+                 min_dimension = 2\n\
+                 max_mass = 70\n\
+                 plausible_density = 1..20000\n\
+                 dense_threshold = 2000\n\
+                 labels = REJECTED=OVERSIZE_HOLD\n\
+                 units = both\n\
+                 output = json\n\
+                 number_format = 1.234,5\n\
+                 map = width=W_MM:mm\n\
+                 map = mass=WEIGHT_LB:lb\n\
+                 dimension_uncertainty = 0.5\n\
+                 mass_uncertainty = 0.1\n",
+            )
+            .unwrap();
+        assert_eq!(
+            config.rules.axis_limits,
+            Some(AxisLimits::new(
+                Centimeters::new(200.0),
+                Centimeters::new(150.0),
+                Centimeters::new(150.0)
+            ))
+        );
+        assert_eq!(config.rules.max_mass, Some(Kilograms::new(70.0)));
+        assert_eq!(config.rules.min_dimension, Some(Centimeters::new(2.0)));
+        assert_eq!(config.rules.plausible_density, Some((1.0, 20_000.0)));
+        assert_eq!(config.rules.dense_threshold, Some(2_000.0));
+        assert_eq!(config.labels.label(SortCategory::Rejected), "OVERSIZE_HOLD");
+        assert_eq!(
+            config.category_label(SortCategory::Rejected),
+            "OVERSIZE_HOLD"
+        );
+        assert_eq!(config.columns.mappings().count(), 2);
+        assert_eq!(config.number_format(), NumberFormat::COMMA);
+        assert_eq!(
+            config.uncertainty,
+            Uncertainty::new(Centimeters::new(0.5), Kilograms::new(0.1))
+        );
+
+        let mut reparsed = Config::default();
+        reparsed.apply_str(&config.to_string()).unwrap();
+        assert_eq!(reparsed, config);
+    }
+
+    #[test]
+    fn test_named_profiles() {
+        let mut config = Config::default();
+        config
+            .apply_str(
+                "mass_threshold = 25\n\
+                 [profile freight]\n\
+                 mass_threshold = 40\n\
+                 [profile international]\n\
+                 dimension_threshold = 120\n",
+            )
+            .unwrap();
+
+        let freight = config.rules_for(Some("freight")).unwrap();
+        assert_eq!(freight.mass_threshold, Kilograms::new(40.0));
+        assert_eq!(freight.profile.as_deref(), Some("freight"));
+
+        // Profiles inherit the base settings they don't override
+        let international = config.rules_for(Some("international")).unwrap();
+        assert_eq!(international.mass_threshold, Kilograms::new(25.0));
+        assert_eq!(international.dimension_threshold, Centimeters::new(120.0));
+
+        // The profile name is recorded in each decision
+        let package = crate::Package::new(
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Kilograms::new(30.0),
+        );
+        let decision = freight.decide(&package);
+        assert_eq!(decision.category, SortCategory::Standard);
+        assert_eq!(decision.profile.as_deref(), Some("freight"));
+        assert_eq!(
+            config.rules_for(None).unwrap().decide(&package).profile,
+            None
+        );
+
+        assert!(matches!(
+            config.rules_for(Some("domestic")),
+            Err(ConfigError::UnknownProfile(_))
+        ));
+
+        config.set("profile", "freight").unwrap();
+        let mut reparsed = Config::default();
+        reparsed.apply_str(&config.to_string()).unwrap();
+        assert_eq!(reparsed, config);
+    }
+
+    #[test]
+    fn test_scheduled_overrides() {
+        let mut config = Config::default();
+        config
+            .apply_str(
+                "[override peak-season]\n\
+                 dates = 2024-11-25..2024-12-24\n\
+                 mass_threshold = 18\n\
+                 [profile freight]\n\
+                 mass_threshold = 40\n",
+            )
+            .unwrap();
+        assert_eq!(config.rules.schedule.len(), 1);
+
+        // 2024-11-29T14:30:00Z
+        let black_friday = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_732_890_600);
+        let rules = config.rules_for(Some("freight")).unwrap().at(black_friday);
+        assert_eq!(rules.mass_threshold, Kilograms::new(18.0));
+        assert_eq!(rules.version(), "freight+peak-season");
+
+        let rules = config.active_rules().unwrap().at(std::time::UNIX_EPOCH);
+        assert_eq!(rules.mass_threshold, Kilograms::new(20.0));
+        assert_eq!(rules.version(), "base");
+
+        let mut reparsed = Config::default();
+        reparsed.apply_str(&config.to_string()).unwrap();
+        assert_eq!(reparsed, config);
+
+        assert!(matches!(
+            config.apply_str("[override peak]\ndates = 2024-12-24..2024-11-25"),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            config.apply_str("[override peak]\naperture = 100x100"),
+            Err(ConfigError::UnknownKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_errors() {
+        let mut config = Config::default();
+        assert!(matches!(
+            config.set("mass_limit", "25"),
+            Err(ConfigError::UnknownKey(_))
+        ));
+        assert!(matches!(
+            config.set("mass_threshold", "heavy"),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            config.set("mass_uncertainty", "-0.1"),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            config.apply_str("mass_threshold 25"),
+            Err(ConfigError::Syntax { line: 1, .. })
+        ));
+        assert!(matches!(
+            Config::load(&strings(&["--locale"]), Vec::new()),
+            Err(ConfigError::MissingValue(_))
+        ));
+        assert!(matches!(
+            Config::load(&strings(&["--profile", "freight"]), Vec::new()),
+            Err(ConfigError::UnknownProfile(_))
+        ));
+        assert!(matches!(
+            config.apply_str("[profile freight]\nlocale = es"),
+            Err(ConfigError::UnknownKey(_))
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{Package, SortCategory, MASS_THRESHOLD, VOLUME_THRESHOLD};
+
+/// Fraction of a box that can realistically be filled when packing several
+/// items together
+pub const FILL_FACTOR: f64 = 0.8;
+
+/// A group of packages that could share one box
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsolidationGroup {
+    /// Destination shared by every package in the group
+    pub destination: String,
+    /// Indices of the grouped packages in the input batch
+    pub items: Vec<usize>,
+    /// Combined volume of the grouped packages in cubic centimeters
+    pub combined_volume: f64,
+    /// Combined mass of the grouped packages in kilograms
+    pub combined_mass: f64,
+}
+
+impl ConsolidationGroup {
+    /// Number of boxes saved by shipping the group as one box
+    pub fn boxes_saved(&self) -> usize {
+        self.items.len().saturating_sub(1)
+    }
+}
+
+/// Consolidation suggestions for a batch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConsolidationPlan {
+    pub groups: Vec<ConsolidationGroup>,
+}
+
+impl ConsolidationPlan {
+    /// Total number of boxes saved across all groups
+    pub fn boxes_saved(&self) -> usize {
+        self.groups
+            .iter()
+            .map(ConsolidationGroup::boxes_saved)
+            .sum()
+    }
+}
+
+/// Suggests groups of STANDARD packages going to the same destination that
+/// could be consolidated into one box without it becoming bulky or heavy.
+///
+/// The combined box is estimated from the summed volume of its contents,
+/// inflated by [`FILL_FACTOR`]. Packages are grouped first-fit, largest
+/// first. Only groups of two or more packages are reported.
+///
+/// # Arguments
+/// * `batch` - `(destination, package)` pairs
+pub fn suggest_consolidation(batch: &[(&str, Package)]) -> ConsolidationPlan {
+    let mut destinations: Vec<&str> = Vec::new();
+    for (destination, _) in batch {
+        if !destinations.contains(destination) {
+            destinations.push(destination);
+        }
+    }
+
+    let mut plan = ConsolidationPlan::default();
+    for destination in destinations {
+        let mut candidates: Vec<usize> = batch
+            .iter()
+            .enumerate()
+            .filter(|(_, (dest, pkg))| {
+                *dest == destination && pkg.sort_category() == SortCategory::Standard
+            })
+            .map(|(i, _)| i)
+            .collect();
+        candidates.sort_by(|&a, &b| batch[b].1.volume().total_cmp(&batch[a].1.volume()));
+
+        let mut groups: Vec<ConsolidationGroup> = Vec::new();
+        for index in candidates {
+            let package = &batch[index].1;
+            let volume = package.volume();
+            let mass = package.mass.value();
+
+            let fits = |group: &ConsolidationGroup| {
+                (group.combined_volume + volume) / FILL_FACTOR < VOLUME_THRESHOLD
+                    && group.combined_mass + mass < MASS_THRESHOLD
+            };
+
+            match groups.iter_mut().find(|group| fits(group)) {
+                Some(group) => {
+                    group.items.push(index);
+                    group.combined_volume += volume;
+                    group.combined_mass += mass;
+                }
+                None => groups.push(ConsolidationGroup {
+                    destination: destination.to_string(),
+                    items: vec![index],
+                    combined_volume: volume,
+                    combined_mass: mass,
+                }),
+            }
+        }
+
+        plan.groups
+            .extend(groups.into_iter().filter(|group| group.items.len() > 1));
+    }
+
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Centimeters, Kilograms};
+
+    fn package(side: f64, mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(side),
+            Centimeters::new(side),
+            Centimeters::new(side),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_consolidation_by_destination() {
+        let batch = [
+            ("NYC", package(20.0, 2.0)),
+            ("LAX", package(20.0, 2.0)),
+            ("NYC", package(30.0, 3.0)),
+            ("NYC", package(10.0, 1.0)),
+            // Heavy packages are never consolidated
+            ("NYC", package(10.0, 25.0)),
+        ];
+
+        let plan = suggest_consolidation(&batch);
+        assert_eq!(plan.groups.len(), 1);
+        assert_eq!(plan.groups[0].destination, "NYC");
+        assert_eq!(plan.groups[0].items, vec![2, 0, 3]);
+        assert_eq!(plan.boxes_saved(), 2);
+    }
+
+    #[test]
+    fn test_consolidation_respects_limits() {
+        // Two 12 kg packages would together be heavy
+        let batch = [("NYC", package(20.0, 12.0)), ("NYC", package(20.0, 12.0))];
+        assert_eq!(suggest_consolidation(&batch).boxes_saved(), 0);
+
+        // Three 70 cm cubes would together be bulky once packed
+        let batch = [
+            ("NYC", package(70.0, 1.0)),
+            ("NYC", package(70.0, 1.0)),
+            ("NYC", package(70.0, 1.0)),
+        ];
+        let plan = suggest_consolidation(&batch);
+        assert_eq!(plan.groups.len(), 1);
+        assert_eq!(plan.groups[0].items.len(), 2);
+    }
+}
@@ -0,0 +1,259 @@
+//! Localized display strings.
+//!
+//! Only human-facing text is translated; the machine-readable identifiers
+//! (`SortCategory::as_str`, `Reason::as_str`) always stay English.
+
+use std::fmt::{self, Write};
+
+use crate::numbers::NumberFormat;
+use crate::{Reason, SortCategory};
+
+/// A language human-facing output can be rendered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+    French,
+    German,
+}
+
+/// A message printed by the command-line program. Messages with `{}`
+/// placeholders are filled in with [`Locale::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    /// The program banner
+    Title,
+    /// `usage: {command line}`
+    Usage,
+    /// `review actions: {actions}`
+    ReviewActions,
+    /// `error: {error}`
+    Error,
+    /// `{changed} of {replayed} packages change category`
+    CategoryChanges,
+    /// `queued {queued} of {total} packages for review`
+    Queued,
+    /// `{package id}: approved as {category}`
+    Approved,
+    /// `{pending} pending, {resolved} resolved`
+    ReviewSummary,
+    /// `line {line}: {reason}`
+    RowError,
+    /// `{rows} rows: {sorted} sorted, {malformed} malformed`
+    BatchSummary,
+    /// Descriptions of the example packages
+    StandardExample,
+    BulkyByVolumeExample,
+    BulkyByDimensionExample,
+    HeavyExample,
+    BulkyAndHeavyExample,
+}
+
+impl Locale {
+    /// Parses a language tag such as `es` or `fr-CA`, ignoring the region
+    /// (`None` if the language isn't supported)
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::English),
+            "es" => Some(Locale::Spanish),
+            "fr" => Some(Locale::French),
+            "de" => Some(Locale::German),
+            _ => None,
+        }
+    }
+
+    /// Returns the language tag of the locale
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Spanish => "es",
+            Locale::French => "fr",
+            Locale::German => "de",
+        }
+    }
+
+    /// Returns how numbers are written in this locale
+    pub fn number_format(&self) -> NumberFormat {
+        match self {
+            Locale::English => NumberFormat::POINT,
+            Locale::Spanish | Locale::German => NumberFormat::COMMA,
+            Locale::French => NumberFormat::new(',', Some(' ')),
+        }
+    }
+
+    /// Returns the display name of a category in this locale
+    pub fn category(&self, category: SortCategory) -> &'static str {
+        use SortCategory::*;
+
+        match (self, category) {
+            (Locale::English, _) => category.as_str(),
+            (Locale::Spanish, Standard) => "ESTÁNDAR",
+            (Locale::Spanish, Special) => "ESPECIAL",
+            (Locale::Spanish, Perishable) => "PERECEDERO",
+            (Locale::Spanish, Rejected) => "RECHAZADO",
+            (Locale::French, Standard) => "STANDARD",
+            (Locale::French, Special) => "SPÉCIAL",
+            (Locale::French, Perishable) => "PÉRISSABLE",
+            (Locale::French, Rejected) => "REFUSÉ",
+            (Locale::German, Standard) => "STANDARD",
+            (Locale::German, Special) => "SONDERBEHANDLUNG",
+            (Locale::German, Perishable) => "VERDERBLICH",
+            (Locale::German, Rejected) => "ABGELEHNT",
+        }
+    }
+
+    /// Returns the display text of a reason in this locale
+    pub fn reason(&self, reason: Reason) -> &'static str {
+        use Reason::*;
+
+        match (self, reason) {
+            (Locale::English, Bulky) => "bulky",
+            (Locale::English, Heavy) => "heavy",
+            (Locale::English, Perishable) => "perishable",
+            (Locale::English, TooSmall) => "too small",
+            (Locale::English, Overweight) => "overweight",
+            (Locale::English, Dense) => "dense",
+            (Locale::Spanish, Bulky) => "voluminoso",
+            (Locale::Spanish, Heavy) => "pesado",
+            (Locale::Spanish, Perishable) => "perecedero",
+            (Locale::Spanish, TooSmall) => "demasiado pequeño",
+            (Locale::Spanish, Overweight) => "exceso de peso",
+            (Locale::Spanish, Dense) => "denso",
+            (Locale::French, Bulky) => "volumineux",
+            (Locale::French, Heavy) => "lourd",
+            (Locale::French, Perishable) => "périssable",
+            (Locale::French, TooSmall) => "trop petit",
+            (Locale::French, Overweight) => "surpoids",
+            (Locale::French, Dense) => "dense",
+            (Locale::German, Bulky) => "sperrig",
+            (Locale::German, Heavy) => "schwer",
+            (Locale::German, Perishable) => "verderblich",
+            (Locale::German, TooSmall) => "zu klein",
+            (Locale::German, Overweight) => "übergewichtig",
+            (Locale::German, Dense) => "dicht",
+        }
+    }
+
+    /// Returns the text of a command-line message in this locale, with its
+    /// placeholders
+    pub fn message(&self, message: Message) -> &'static str {
+        use Message::*;
+
+        match (self, message) {
+            (Locale::English, Title) => "Package Sorting System",
+            (Locale::English, Usage) => "usage: {}",
+            (Locale::English, ReviewActions) => "review actions: {}",
+            (Locale::English, Error) => "error: {}",
+            (Locale::English, CategoryChanges) => "{} of {} packages change category",
+            (Locale::English, Queued) => "queued {} of {} packages for review",
+            (Locale::English, Approved) => "{}: approved as {}",
+            (Locale::English, ReviewSummary) => "{} pending, {} resolved",
+            (Locale::English, RowError) => "line {}: {}",
+            (Locale::English, BatchSummary) => "{} rows: {} sorted, {} malformed",
+            (Locale::English, StandardExample) => "Standard package",
+            (Locale::English, BulkyByVolumeExample) => "Bulky by volume",
+            (Locale::English, BulkyByDimensionExample) => "Bulky by dimension",
+            (Locale::English, HeavyExample) => "Heavy package",
+            (Locale::English, BulkyAndHeavyExample) => "Bulky and heavy",
+            (Locale::Spanish, Title) => "Sistema de clasificación de paquetes",
+            (Locale::Spanish, Usage) => "uso: {}",
+            (Locale::Spanish, ReviewActions) => "acciones de review: {}",
+            (Locale::Spanish, Error) => "error: {}",
+            (Locale::Spanish, CategoryChanges) => "{} de {} paquetes cambian de categoría",
+            (Locale::Spanish, Queued) => "{} de {} paquetes en cola de revisión",
+            (Locale::Spanish, Approved) => "{}: aprobado como {}",
+            (Locale::Spanish, ReviewSummary) => "{} pendientes, {} resueltos",
+            (Locale::Spanish, RowError) => "línea {}: {}",
+            (Locale::Spanish, BatchSummary) => "{} filas: {} clasificadas, {} con errores",
+            (Locale::Spanish, StandardExample) => "Paquete estándar",
+            (Locale::Spanish, BulkyByVolumeExample) => "Voluminoso por volumen",
+            (Locale::Spanish, BulkyByDimensionExample) => "Voluminoso por dimensión",
+            (Locale::Spanish, HeavyExample) => "Paquete pesado",
+            (Locale::Spanish, BulkyAndHeavyExample) => "Voluminoso y pesado",
+            (Locale::French, Title) => "Système de tri des colis",
+            (Locale::French, Usage) => "utilisation : {}",
+            (Locale::French, ReviewActions) => "actions de review : {}",
+            (Locale::French, Error) => "erreur : {}",
+            (Locale::French, CategoryChanges) => "{} colis sur {} changent de catégorie",
+            (Locale::French, Queued) => "{} colis sur {} mis en file de révision",
+            (Locale::French, Approved) => "{} : approuvé comme {}",
+            (Locale::French, ReviewSummary) => "{} en attente, {} traités",
+            (Locale::French, RowError) => "ligne {} : {}",
+            (Locale::French, BatchSummary) => "{} lignes : {} triées, {} mal formées",
+            (Locale::French, StandardExample) => "Colis standard",
+            (Locale::French, BulkyByVolumeExample) => "Volumineux par le volume",
+            (Locale::French, BulkyByDimensionExample) => "Volumineux par une dimension",
+            (Locale::French, HeavyExample) => "Colis lourd",
+            (Locale::French, BulkyAndHeavyExample) => "Volumineux et lourd",
+            (Locale::German, Title) => "Paketsortiersystem",
+            (Locale::German, Usage) => "Aufruf: {}",
+            (Locale::German, ReviewActions) => "review-Aktionen: {}",
+            (Locale::German, Error) => "Fehler: {}",
+            (Locale::German, CategoryChanges) => "{} von {} Paketen wechseln die Kategorie",
+            (Locale::German, Queued) => "{} von {} Paketen zur Prüfung eingereiht",
+            (Locale::German, Approved) => "{}: bestätigt als {}",
+            (Locale::German, ReviewSummary) => "{} offen, {} erledigt",
+            (Locale::German, RowError) => "Zeile {}: {}",
+            (Locale::German, BatchSummary) => "{} Zeilen: {} sortiert, {} fehlerhaft",
+            (Locale::German, StandardExample) => "Standardpaket",
+            (Locale::German, BulkyByVolumeExample) => "Sperrig nach Volumen",
+            (Locale::German, BulkyByDimensionExample) => "Sperrig nach Abmessung",
+            (Locale::German, HeavyExample) => "Schweres Paket",
+            (Locale::German, BulkyAndHeavyExample) => "Sperrig und schwer",
+        }
+    }
+
+    /// Returns a command-line message in this locale with each `{}` replaced
+    /// by the next argument
+    pub fn format(&self, message: Message, args: &[&dyn fmt::Display]) -> String {
+        let mut parts = self.message(message).split("{}");
+        let mut text = parts.next().unwrap_or_default().to_string();
+        for (part, arg) in parts.zip(args) {
+            let _ = write!(text, "{}", arg);
+            text.push_str(part);
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("es"), Some(Locale::Spanish));
+        assert_eq!(Locale::from_tag("fr-CA"), Some(Locale::French));
+        assert_eq!(Locale::from_tag("de_DE"), Some(Locale::German));
+        assert_eq!(Locale::from_tag("xx"), None);
+        assert_eq!(Locale::default().tag(), "en");
+    }
+
+    #[test]
+    fn test_localized_strings() {
+        assert_eq!(Locale::English.category(SortCategory::Rejected), "REJECTED");
+        assert_eq!(
+            Locale::Spanish.category(SortCategory::Rejected),
+            "RECHAZADO"
+        );
+        assert_eq!(Locale::German.reason(Reason::Heavy), "schwer");
+        assert_eq!(
+            Locale::French.message(Message::Title),
+            "Système de tri des colis"
+        );
+
+        assert_eq!(
+            Locale::German.format(Message::CategoryChanges, &[&2, &5]),
+            "2 von 5 Paketen wechseln die Kategorie"
+        );
+        assert_eq!(
+            Locale::Spanish.format(Message::Approved, &[&"PKG-7", &"ESPECIAL"]),
+            "PKG-7: aprobado como ESPECIAL"
+        );
+
+        // Machine-readable identifiers are unaffected
+        assert_eq!(SortCategory::Rejected.as_str(), "REJECTED");
+    }
+}
@@ -0,0 +1,133 @@
+//! Output label remapping for integrating with systems that use their own
+//! category vocabulary.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{ParseCategoryError, SortCategory};
+
+/// Output labels for each category, defaulting to `SortCategory::as_str`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CategoryLabels {
+    overrides: HashMap<SortCategory, String>,
+}
+
+impl CategoryLabels {
+    /// Replaces the output label of a category
+    pub fn set(&mut self, category: SortCategory, label: impl Into<String>) {
+        let label = label.into();
+        if label == category.as_str() {
+            self.overrides.remove(&category);
+        } else {
+            self.overrides.insert(category, label);
+        }
+    }
+
+    /// Returns the output label of a category
+    pub fn label(&self, category: SortCategory) -> &str {
+        self.overrides(category).unwrap_or(category.as_str())
+    }
+
+    /// Returns the label of a category if it has been remapped
+    pub fn overrides(&self, category: SortCategory) -> Option<&str> {
+        self.overrides.get(&category).map(String::as_str)
+    }
+}
+
+impl FromStr for CategoryLabels {
+    type Err = ParseLabelsError;
+
+    /// Parses a comma-separated list of `CATEGORY=LABEL` pairs, e.g.
+    /// `REJECTED=OVERSIZE_HOLD,SPECIAL=MANUAL`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut labels = CategoryLabels::default();
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (category, label) = pair
+                .split_once('=')
+                .ok_or_else(|| ParseLabelsError::MissingLabel(pair.to_string()))?;
+            let label = label.trim();
+            if label.is_empty() {
+                return Err(ParseLabelsError::MissingLabel(pair.to_string()));
+            }
+            labels.set(category.parse()?, label);
+        }
+        Ok(labels)
+    }
+}
+
+impl fmt::Display for CategoryLabels {
+    /// Formats the labels as `CATEGORY=LABEL` pairs, the format accepted by
+    /// `from_str`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs: Vec<String> = SortCategory::ALL
+            .into_iter()
+            .map(|category| format!("{}={}", category, self.label(category)))
+            .collect();
+        write!(f, "{}", pairs.join(","))
+    }
+}
+
+/// Error returned when a label mapping can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseLabelsError {
+    /// An entry isn't of the form `CATEGORY=LABEL`
+    MissingLabel(String),
+    /// An entry names an unknown category
+    UnknownCategory(ParseCategoryError),
+}
+
+impl From<ParseCategoryError> for ParseLabelsError {
+    fn from(err: ParseCategoryError) -> Self {
+        ParseLabelsError::UnknownCategory(err)
+    }
+}
+
+impl fmt::Display for ParseLabelsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseLabelsError::MissingLabel(entry) => {
+                write!(f, "expected CATEGORY=LABEL, got {:?}", entry)
+            }
+            ParseLabelsError::UnknownCategory(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ParseLabelsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_overrides() {
+        let mut labels = CategoryLabels::default();
+        assert_eq!(labels.label(SortCategory::Rejected), "REJECTED");
+
+        labels.set(SortCategory::Rejected, "OVERSIZE_HOLD");
+        assert_eq!(labels.label(SortCategory::Rejected), "OVERSIZE_HOLD");
+        assert_eq!(labels.label(SortCategory::Special), "SPECIAL");
+        assert_eq!(labels.overrides(SortCategory::Special), None);
+    }
+
+    #[test]
+    fn test_parse_labels() {
+        let labels: CategoryLabels = "rejected=OVERSIZE_HOLD, SPECIAL = MANUAL".parse().unwrap();
+        assert_eq!(labels.label(SortCategory::Rejected), "OVERSIZE_HOLD");
+        assert_eq!(labels.label(SortCategory::Special), "MANUAL");
+        assert_eq!(
+            labels.to_string(),
+            "STANDARD=STANDARD,SPECIAL=MANUAL,PERISHABLE=PERISHABLE,REJECTED=OVERSIZE_HOLD"
+        );
+
+        assert!(matches!(
+            "REJECTED".parse::<CategoryLabels>(),
+            Err(ParseLabelsError::MissingLabel(_))
+        ));
+        assert!(matches!(
+            "OVERSIZE=HOLD".parse::<CategoryLabels>(),
+            Err(ParseLabelsError::UnknownCategory(_))
+        ));
+    }
+}
@@ -0,0 +1,1490 @@
+//! Sorts packages into STANDARD, SPECIAL, PERISHABLE and REJECTED lanes by
+//! their dimensions and mass.
+//!
+//! The primary API is [`sort_package`], which applies a [`SortRules`] to a
+//! [`Package`] and returns a [`SortDecision`]. The commonly used types of
+//! the feature modules are re-exported from the crate root.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use predicate::{MassAtLeast, Predicate, VolumeAtLeast};
+use predict::Prediction;
+use schedule::RuleOverride;
+
+pub mod anonymize;
+pub mod audit;
+pub mod balance;
+pub mod batch;
+pub mod bench;
+pub mod cache;
+pub mod catalog;
+pub mod clock;
+pub mod columns;
+pub mod config;
+pub mod consolidation;
+pub mod i18n;
+mod json;
+pub mod labels;
+pub mod numbers;
+pub mod packing;
+pub mod pallet;
+pub mod predicate;
+pub mod predict;
+pub mod record;
+pub mod review;
+pub mod routing;
+pub mod scalar;
+pub mod schedule;
+pub mod selftest;
+pub mod shipment;
+pub mod split;
+pub mod throughput;
+pub mod uncertainty;
+pub mod units;
+pub mod utilization;
+pub mod validate;
+
+pub use audit::{AuditEntry, AuditError, CategoryChange, Sorter};
+pub use clock::{Clock, SystemClock, TestClock};
+pub use config::{Config, ConfigError};
+pub use i18n::Locale;
+pub use labels::CategoryLabels;
+pub use predict::Predictor;
+pub use routing::{RoutingDecision, SizeClass};
+pub use scalar::{Fixed, Scalar};
+pub use schedule::Schedule;
+pub use shipment::{Shipment, ShipmentLimits};
+pub use units::UnitSystem;
+pub use utilization::Container;
+
+/// Volume (cm³) at or above which a package is bulky
+pub const VOLUME_THRESHOLD: f64 = 1_000_000.0;
+
+/// Dimension (cm) at or above which a package is bulky
+pub const DIMENSION_THRESHOLD: f64 = 150.0;
+
+/// Mass (kg) at or above which a package is heavy
+pub const MASS_THRESHOLD: f64 = 20.0;
+
+/// Plausible package density range (kg/m³) used by default to flag suspect
+/// measurements
+pub const PLAUSIBLE_DENSITY: (f64, f64) = (5.0, 10_000.0);
+
+/// Volume (cm³) per kilogram used to compute dimensional weight
+pub const DIM_DIVISOR: f64 = 5_000.0;
+
+/// Stackability score at or above which a package can go on the bottom layer
+pub const BOTTOM_LAYER_STACKABILITY: u8 = 60;
+
+/// Represents a dimension in centimeters (newtype pattern for type safety),
+/// backed by `f64` unless another [`Scalar`] is chosen
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Centimeters<T = f64>(T);
+
+impl<T: Scalar> Centimeters<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn value(&self) -> T {
+        self.0
+    }
+
+    /// Converts to the `f64` backing the rules evaluate in
+    pub fn to_f64(&self) -> Centimeters {
+        Centimeters(self.0.to_f64())
+    }
+}
+
+/// Represents mass in kilograms (newtype pattern for type safety), backed by
+/// `f64` unless another [`Scalar`] is chosen
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Kilograms<T = f64>(T);
+
+impl<T: Scalar> Kilograms<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn value(&self) -> T {
+        self.0
+    }
+
+    /// Converts to the `f64` backing the rules evaluate in
+    pub fn to_f64(&self) -> Kilograms {
+        Kilograms(self.0.to_f64())
+    }
+}
+
+/// Represents a temperature in degrees Celsius (newtype pattern for type safety)
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Celsius(f64);
+
+impl Celsius {
+    pub fn new(value: f64) -> Self {
+        Self(value)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+/// Inclusive temperature range a perishable package must be kept within
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureRange {
+    pub min: Celsius,
+    pub max: Celsius,
+}
+
+impl TemperatureRange {
+    /// Creates a new temperature range from its lower and upper bounds
+    pub fn new(min: Celsius, max: Celsius) -> Self {
+        Self { min, max }
+    }
+
+    /// Checks if the given temperature lies within the range
+    pub fn contains(&self, temperature: Celsius) -> bool {
+        self.min <= temperature && temperature <= self.max
+    }
+}
+
+/// Package sorting category
+///
+/// Variants are ordered by handling severity, so the "worst" of several
+/// categories is simply their maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SortCategory {
+    /// Standard packages (not bulky or heavy) - can be handled normally
+    Standard,
+    /// Special packages (either bulky or heavy) - require special handling
+    Special,
+    /// Perishable packages - routed to the temperature-controlled lane
+    Perishable,
+    /// Rejected packages (both bulky and heavy) - cannot be processed
+    Rejected,
+}
+
+impl fmt::Display for SortCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for SortCategory {
+    type Err = ParseCategoryError;
+
+    /// Parses a category from its string representation (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SortCategory::ALL
+            .into_iter()
+            .find(|category| category.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ParseCategoryError(s.to_string()))
+    }
+}
+
+/// Error returned when a string isn't a known category
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCategoryError(String);
+
+impl fmt::Display for ParseCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown sort category: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseCategoryError {}
+
+impl SortCategory {
+    /// Every category, in order of handling severity
+    pub const ALL: [SortCategory; 4] = [
+        SortCategory::Standard,
+        SortCategory::Special,
+        SortCategory::Perishable,
+        SortCategory::Rejected,
+    ];
+
+    /// Returns the string representation of the category
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortCategory::Standard => "STANDARD",
+            SortCategory::Special => "SPECIAL",
+            SortCategory::Perishable => "PERISHABLE",
+            SortCategory::Rejected => "REJECTED",
+        }
+    }
+}
+
+/// Why a package was routed away from the standard lane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    Bulky,
+    Heavy,
+    Perishable,
+    TooSmall,
+    Overweight,
+    Dense,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Reason {
+    /// Returns the stable, machine-readable identifier of the reason
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reason::Bulky => "BULKY",
+            Reason::Heavy => "HEAVY",
+            Reason::Perishable => "PERISHABLE",
+            Reason::TooSmall => "TOO_SMALL",
+            Reason::Overweight => "OVERWEIGHT",
+            Reason::Dense => "DENSE",
+        }
+    }
+}
+
+/// Why a package's measurements look physically implausible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuspectMeasurement {
+    /// A dimension or the mass is negative or not a number, or a dimension is
+    /// zero
+    NonPhysical,
+    /// Lighter than the plausible density range (e.g. 0.5 kg in a huge box)
+    TooLight,
+    /// Denser than the plausible density range (e.g. 100 kg in a shoebox)
+    TooDense,
+}
+
+impl fmt::Display for SuspectMeasurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl SuspectMeasurement {
+    /// Returns the stable, machine-readable identifier of the anomaly
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuspectMeasurement::NonPhysical => "NON_PHYSICAL",
+            SuspectMeasurement::TooLight => "TOO_LIGHT",
+            SuspectMeasurement::TooDense => "TOO_DENSE",
+        }
+    }
+}
+
+/// The outcome of sorting a single package
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortDecision {
+    /// The category (lane) the package is routed to
+    pub category: SortCategory,
+    /// Whether the package met the bulky rule
+    pub bulky: bool,
+    /// Whether the package met the heavy rule
+    pub heavy: bool,
+    /// Axes at or above their dimension limit
+    pub oversize_axes: Vec<Axis>,
+    /// The measured mass
+    pub actual_weight: Kilograms,
+    /// Bounding box volume divided by the rules' DIM divisor
+    pub dimensional_weight: Kilograms,
+    /// The weight billed: the greater of the actual and dimensional weight
+    pub chargeable_weight: Kilograms,
+    /// Whether the package exceeded the hard mass cap
+    pub overweight: bool,
+    /// Whether the package is in the very dense band
+    pub dense: bool,
+    /// Whether the package is in the suspiciously light band
+    pub light: bool,
+    /// Whether the package is under the minimum dimension and can't ride the
+    /// conveyor
+    pub too_small: bool,
+    /// Required temperature range, if the package is perishable
+    pub temperature: Option<TemperatureRange>,
+    /// Whether the package should be prioritized downstream because it spoils
+    pub time_sensitive: bool,
+    /// How much load the package can bear when stacked, from 0 to 100
+    pub stackability: u8,
+    /// Name of the rule profile the package was sorted under (`None` for the
+    /// base rules)
+    pub profile: Option<String>,
+    /// Identifies the exact rule set applied: the profile name (or `base`)
+    /// followed by `+name` for each time-based override in effect
+    pub rule_version: String,
+    /// Set if the measurements look physically implausible
+    pub suspect: Option<SuspectMeasurement>,
+    /// Advisory predictions attached by [`predict::decide_with_predictors`]
+    pub predictions: Vec<Prediction>,
+    /// Whether the decision should be checked by a person before shipping
+    pub needs_review: bool,
+}
+
+impl SortDecision {
+    /// Lists the reasons the package was routed where it was (empty for a
+    /// plain standard package)
+    pub fn reasons(&self) -> Vec<Reason> {
+        let mut reasons = Vec::new();
+        if self.bulky {
+            reasons.push(Reason::Bulky);
+        }
+        if self.heavy {
+            reasons.push(Reason::Heavy);
+        }
+        if self.temperature.is_some() {
+            reasons.push(Reason::Perishable);
+        }
+        if self.too_small {
+            reasons.push(Reason::TooSmall);
+        }
+        if self.overweight {
+            reasons.push(Reason::Overweight);
+        }
+        if self.dense {
+            reasons.push(Reason::Dense);
+        }
+        reasons
+    }
+
+    /// Checks if the package is sturdy enough for the bottom layer of a pallet
+    pub fn can_be_bottom_layer(&self) -> bool {
+        self.stackability >= BOTTOM_LAYER_STACKABILITY
+    }
+}
+
+/// The physical shape of a package
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Shape {
+    /// A rectangular box described by the package's width, height and length
+    #[default]
+    Box,
+    /// A cylinder, such as a poster tube or rolled carpet
+    Cylinder {
+        diameter: Centimeters,
+        length: Centimeters,
+    },
+    /// An irregular item, described only by its bounding box
+    Irregular { bounding_box: [Centimeters; 3] },
+}
+
+/// Represents a package with dimensions and mass
+///
+/// For non-box shapes, `width`, `height` and `length` hold the bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Package {
+    pub width: Centimeters,
+    pub height: Centimeters,
+    pub length: Centimeters,
+    pub mass: Kilograms,
+    /// Required temperature range for perishable packages (`None` if not perishable)
+    pub perishable: Option<TemperatureRange>,
+    pub shape: Shape,
+    /// Whether the contents are fragile and must not bear any load
+    pub fragile: bool,
+    /// Whether the contents are hazardous materials
+    pub hazmat: bool,
+}
+
+impl Package {
+    /// Creates a new package with the given dimensions and mass
+    pub fn new(width: Centimeters, height: Centimeters, length: Centimeters, mass: Kilograms) -> Self {
+        Self {
+            width,
+            height,
+            length,
+            mass,
+            perishable: None,
+            shape: Shape::Box,
+            fragile: false,
+            hazmat: false,
+        }
+    }
+
+    /// Creates a new cylindrical package (tube, roll) with the given diameter,
+    /// length and mass
+    pub fn cylinder(diameter: Centimeters, length: Centimeters, mass: Kilograms) -> Self {
+        Self {
+            shape: Shape::Cylinder { diameter, length },
+            ..Self::new(diameter, diameter, length, mass)
+        }
+    }
+
+    /// Creates a new irregularly shaped package from its bounding box
+    /// (width, height, length) and mass
+    pub fn irregular(bounding_box: [Centimeters; 3], mass: Kilograms) -> Self {
+        let [width, height, length] = bounding_box;
+        Self {
+            shape: Shape::Irregular { bounding_box },
+            ..Self::new(width, height, length, mass)
+        }
+    }
+
+    /// Marks the package as perishable, requiring the given temperature range
+    pub fn with_temperature_range(mut self, range: TemperatureRange) -> Self {
+        self.perishable = Some(range);
+        self
+    }
+
+    /// Checks if the package is perishable
+    pub fn is_perishable(&self) -> bool {
+        self.perishable.is_some()
+    }
+
+    /// Marks the package as fragile
+    pub fn with_fragile(mut self) -> Self {
+        self.fragile = true;
+        self
+    }
+
+    /// Marks the package as containing hazardous materials
+    pub fn with_hazmat(mut self) -> Self {
+        self.hazmat = true;
+        self
+    }
+
+    /// Returns a copy of the package with its dimensions sorted descending
+    /// (`length` >= `width` >= `height`), so that rules referring to "length"
+    /// don't depend on how the dimensions were entered and equal boxes compare
+    /// equal.
+    pub fn normalized(&self) -> Self {
+        let mut dims = [self.width, self.height, self.length];
+        dims.sort_by(|a, b| b.value().total_cmp(&a.value()));
+        let [length, width, height] = dims;
+
+        Self {
+            width,
+            height,
+            length,
+            ..*self
+        }
+    }
+
+    /// Calculates the volume of the package in cubic centimeters.
+    ///
+    /// Cylinders use their true volume; boxes and irregular items use their
+    /// (bounding) box.
+    pub fn volume(&self) -> f64 {
+        match self.shape {
+            Shape::Cylinder { diameter, length } => {
+                let radius = diameter.value() / 2.0;
+                std::f64::consts::PI * radius * radius * length.value()
+            }
+            Shape::Box | Shape::Irregular { .. } => {
+                self.width.value() * self.height.value() * self.length.value()
+            }
+        }
+    }
+
+    /// Calculates the density of the package in kg/m³
+    pub fn density(&self) -> f64 {
+        self.mass.value() / (self.volume() / 1_000_000.0)
+    }
+
+    /// Calculates the dimensional weight of the package under the default
+    /// rules: its bounding box volume divided by 5,000 cm³/kg
+    pub fn dimensional_weight(&self) -> Kilograms {
+        SortRules::default().dimensional_weight(self)
+    }
+
+    /// Calculates the chargeable weight under the default rules: the greater
+    /// of the actual and the dimensional weight
+    pub fn chargeable_weight(&self) -> Kilograms {
+        SortRules::default().chargeable_weight(self)
+    }
+
+    /// Orders packages by volume, smallest first
+    pub fn cmp_by_volume(&self, other: &Self) -> Ordering {
+        self.volume().total_cmp(&other.volume())
+    }
+
+    /// Orders packages by chargeable weight, lightest first
+    pub fn cmp_by_chargeable_weight(&self, other: &Self) -> Ordering {
+        self.chargeable_weight()
+            .value()
+            .total_cmp(&other.chargeable_weight().value())
+    }
+
+    /// Checks whether every dimension and the mass of this package are at
+    /// least those of `other`, regardless of how either was oriented
+    pub fn dominates(&self, other: &Self) -> bool {
+        let (a, b) = (self.normalized(), other.normalized());
+        a.length >= b.length && a.width >= b.width && a.height >= b.height && a.mass >= b.mass
+    }
+
+    /// Scores how much load the package can bear when stacked, from 0
+    /// (must go on top) to 100 (ideal bottom layer).
+    ///
+    /// Fragile packages always score 0. Otherwise up to 60 points come from
+    /// density (full marks at 500 kg/m³) and up to 40 from the footprint
+    /// it rests on (full marks at 5,000 cm²).
+    pub fn stackability(&self) -> u8 {
+        const FULL_DENSITY: f64 = 500.0;
+        const FULL_FOOTPRINT: f64 = 5_000.0;
+
+        if self.fragile {
+            return 0;
+        }
+
+        let resting = self.normalized();
+        let footprint = resting.length.value() * resting.width.value();
+        let density_score = (self.density() / FULL_DENSITY).clamp(0.0, 1.0) * 60.0;
+        let footprint_score = (footprint / FULL_FOOTPRINT).clamp(0.0, 1.0) * 40.0;
+
+        (density_score + footprint_score).round() as u8
+    }
+
+    /// Checks whether the package can pass through a rectangular opening in
+    /// any orientation
+    pub fn fits_through(&self, aperture: &Aperture) -> bool {
+        let package = self.normalized();
+        let (small, large) = if aperture.width <= aperture.height {
+            (aperture.width, aperture.height)
+        } else {
+            (aperture.height, aperture.width)
+        };
+
+        package.width <= large && package.height <= small
+    }
+
+    /// Checks if the package is bulky according to the default rules:
+    /// - Volume >= 1,000,000 cm³, OR
+    /// - Any dimension >= 150 cm
+    pub fn is_bulky(&self) -> bool {
+        SortRules::default().is_bulky(self)
+    }
+
+    /// Checks if the package is heavy (mass >= 20 kg)
+    pub fn is_heavy(&self) -> bool {
+        SortRules::default().is_heavy(self)
+    }
+
+    /// Determines the sort category for this package
+    pub fn sort_category(&self) -> SortCategory {
+        self.decide().category
+    }
+
+    /// Sorts the package under the default rules and reports why it was
+    /// routed where it was
+    pub fn decide(&self) -> SortDecision {
+        SortRules::default().decide(self)
+    }
+}
+
+/// Anything that can be sorted: implement it for your own domain types to
+/// classify them without copying their fields into a [`Package`]
+pub trait Sortable {
+    /// Returns the width, height and length
+    fn dims(&self) -> [Centimeters; 3];
+
+    /// Returns the mass
+    fn mass(&self) -> Kilograms;
+
+    /// Returns the package the rules evaluate. Override to carry a shape,
+    /// temperature range or handling flags.
+    fn to_package(&self) -> Package {
+        let [width, height, length] = self.dims();
+        Package::new(width, height, length, self.mass())
+    }
+}
+
+impl Sortable for Package {
+    fn dims(&self) -> [Centimeters; 3] {
+        [self.width, self.height, self.length]
+    }
+
+    fn mass(&self) -> Kilograms {
+        self.mass
+    }
+
+    fn to_package(&self) -> Package {
+        *self
+    }
+}
+
+/// A rectangular opening (door, conveyor gate) packages must pass through
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aperture {
+    pub width: Centimeters,
+    pub height: Centimeters,
+}
+
+impl Aperture {
+    /// Creates a new aperture with the given opening size
+    pub fn new(width: Centimeters, height: Centimeters) -> Self {
+        Self { width, height }
+    }
+}
+
+/// An axis of a normalized package (`length` >= `width` >= `height`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Length,
+    Width,
+    Height,
+}
+
+impl fmt::Display for Axis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Axis {
+    /// Returns the stable, machine-readable identifier of the axis
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Axis::Length => "LENGTH",
+            Axis::Width => "WIDTH",
+            Axis::Height => "HEIGHT",
+        }
+    }
+}
+
+/// Per-axis dimensions at or above which a package is bulky, applied to the
+/// normalized package
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisLimits {
+    pub length: Centimeters,
+    pub width: Centimeters,
+    pub height: Centimeters,
+}
+
+impl AxisLimits {
+    /// Creates per-axis limits for the longest, middle and shortest sides
+    pub fn new(length: Centimeters, width: Centimeters, height: Centimeters) -> Self {
+        Self {
+            length,
+            width,
+            height,
+        }
+    }
+}
+
+/// Configurable thresholds used to sort packages
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortRules {
+    /// Volume (cm³) at or above which a package is bulky
+    pub volume_threshold: f64,
+    /// Dimension at or above which a package is bulky
+    pub dimension_threshold: Centimeters,
+    /// Per-axis limits used instead of `dimension_threshold` when set
+    pub axis_limits: Option<AxisLimits>,
+    /// Mass at or above which a package is heavy
+    pub mass_threshold: Kilograms,
+    /// Mass at or above which a package is rejected regardless of its size,
+    /// e.g. a lifting-safety limit (`None` disables the cap)
+    pub max_mass: Option<Kilograms>,
+    /// Opening every package must fit through; packages that don't are bulky
+    pub aperture: Option<Aperture>,
+    /// Dimension below which a package can't ride the conveyor (`None`
+    /// disables the check)
+    pub min_dimension: Option<Centimeters>,
+    /// Name of the profile these rules were selected from (`None` for the
+    /// base rules)
+    pub profile: Option<String>,
+    /// Plausible density range (kg/m³); packages outside it are flagged as
+    /// suspect measurements. `None` disables the check.
+    pub plausible_density: Option<(f64, f64)>,
+    /// Volume (cm³) per kilogram used to compute dimensional weight
+    pub dim_divisor: f64,
+    /// Density (kg/m³) at or above which a package needs special handling
+    /// even if it isn't heavy
+    pub dense_threshold: Option<f64>,
+    /// Density (kg/m³) below which a package is suspiciously light and
+    /// flagged for review
+    pub light_threshold: Option<f64>,
+    /// Time-based overrides, applied by [`SortRules::at`]
+    pub schedule: Vec<RuleOverride>,
+    /// Names of the overrides applied by [`SortRules::at`]
+    pub applied_overrides: Vec<String>,
+}
+
+impl Default for SortRules {
+    fn default() -> Self {
+        Self {
+            volume_threshold: VOLUME_THRESHOLD,
+            dimension_threshold: Centimeters::new(DIMENSION_THRESHOLD),
+            axis_limits: None,
+            mass_threshold: Kilograms::new(MASS_THRESHOLD),
+            max_mass: None,
+            aperture: None,
+            min_dimension: None,
+            profile: None,
+            plausible_density: Some(PLAUSIBLE_DENSITY),
+            dim_divisor: DIM_DIVISOR,
+            dense_threshold: None,
+            light_threshold: None,
+            schedule: Vec::new(),
+            applied_overrides: Vec::new(),
+        }
+    }
+}
+
+impl SortRules {
+    /// Returns the rules in effect at the given time, with every scheduled
+    /// override that is active applied in order
+    pub fn at(&self, time: SystemTime) -> SortRules {
+        let mut rules = self.clone();
+        for rule_override in &self.schedule {
+            if rule_override.schedule.is_active(time) {
+                rule_override.apply(&mut rules);
+                rules.applied_overrides.push(rule_override.name.clone());
+            }
+        }
+        rules
+    }
+
+    /// Identifies the rule set: the profile name (or `base`) followed by
+    /// `+name` for each applied override
+    pub fn version(&self) -> String {
+        let mut version = self.profile.clone().unwrap_or_else(|| "base".to_string());
+        for name in &self.applied_overrides {
+            version.push('+');
+            version.push_str(name);
+        }
+        version
+    }
+
+    /// Checks if the package is bulky under these rules:
+    /// - Volume >= `volume_threshold`, OR
+    /// - Any dimension >= `dimension_threshold` (or its `axis_limits`), OR
+    /// - It doesn't fit through the configured `aperture`
+    pub fn is_bulky<S: Sortable + ?Sized>(&self, item: &S) -> bool {
+        let package = item.to_package();
+        VolumeAtLeast(self.volume_threshold).test(&package)
+            || !self.oversize_axes(&package).is_empty()
+            || self
+                .aperture
+                .is_some_and(|aperture| !package.fits_through(&aperture))
+    }
+
+    /// Lists the axes of the normalized package at or above their limit:
+    /// `axis_limits` if set, otherwise `dimension_threshold` for every axis
+    pub fn oversize_axes(&self, package: &Package) -> Vec<Axis> {
+        let package = package.normalized();
+        let limits = self.axis_limits.unwrap_or(AxisLimits::new(
+            self.dimension_threshold,
+            self.dimension_threshold,
+            self.dimension_threshold,
+        ));
+
+        [
+            (Axis::Length, package.length, limits.length),
+            (Axis::Width, package.width, limits.width),
+            (Axis::Height, package.height, limits.height),
+        ]
+        .into_iter()
+        .filter(|(_, dimension, limit)| dimension >= limit)
+        .map(|(axis, _, _)| axis)
+        .collect()
+    }
+
+    /// Checks if the package is heavy (mass >= `mass_threshold`)
+    pub fn is_heavy<S: Sortable + ?Sized>(&self, item: &S) -> bool {
+        MassAtLeast(self.mass_threshold).test(&item.to_package())
+    }
+
+    /// Checks if the package is at or above the configured `max_mass`
+    pub fn is_overweight(&self, package: &Package) -> bool {
+        self.max_mass.is_some_and(|max| package.mass >= max)
+    }
+
+    /// Calculates the dimensional weight of the package: its bounding box
+    /// volume divided by `dim_divisor`
+    pub fn dimensional_weight(&self, package: &Package) -> Kilograms {
+        let bounding_volume =
+            package.width.value() * package.height.value() * package.length.value();
+        Kilograms::new(bounding_volume / self.dim_divisor)
+    }
+
+    /// Calculates the chargeable weight: the greater of the actual and the
+    /// dimensional weight
+    pub fn chargeable_weight(&self, package: &Package) -> Kilograms {
+        let dimensional = self.dimensional_weight(package);
+        Kilograms::new(package.mass.value().max(dimensional.value()))
+    }
+
+    /// Checks if the package's density is at or above `dense_threshold`
+    pub fn is_dense(&self, package: &Package) -> bool {
+        self.dense_threshold
+            .is_some_and(|threshold| package.density() >= threshold)
+    }
+
+    /// Checks if the package's density is below `light_threshold`
+    pub fn is_light(&self, package: &Package) -> bool {
+        self.light_threshold
+            .is_some_and(|threshold| package.density() < threshold)
+    }
+
+    /// Checks if any dimension is under the configured `min_dimension`
+    pub fn is_too_small(&self, package: &Package) -> bool {
+        self.min_dimension
+            .is_some_and(|min| package.width < min || package.height < min || package.length < min)
+    }
+
+    /// Checks the measurements for physically implausible values
+    pub fn check_plausibility(&self, package: &Package) -> Option<SuspectMeasurement> {
+        let dimensions = [package.width, package.height, package.length];
+        if dimensions
+            .iter()
+            .any(|d| !d.value().is_finite() || d.value() <= 0.0)
+            || !package.mass.value().is_finite()
+            || package.mass.value() < 0.0
+        {
+            return Some(SuspectMeasurement::NonPhysical);
+        }
+
+        let (min, max) = self.plausible_density?;
+        let density = package.density();
+        if density < min {
+            Some(SuspectMeasurement::TooLight)
+        } else if density > max {
+            Some(SuspectMeasurement::TooDense)
+        } else {
+            None
+        }
+    }
+
+    /// Sorts the package and reports why it was routed where it was.
+    ///
+    /// Perishable packages go to the temperature-controlled lane unless they
+    /// are rejected outright (bulky and heavy, or at the hard mass cap).
+    /// Packages too small for the conveyor or in the dense band need special
+    /// handling. Implausible measurements are still sorted, but flagged as
+    /// suspect and for review, as are packages in the light band.
+    pub fn decide<S: Sortable + ?Sized>(&self, item: &S) -> SortDecision {
+        let package = &item.to_package();
+        let bulky = self.is_bulky(package);
+        let heavy = self.is_heavy(package);
+        let overweight = self.is_overweight(package);
+        let too_small = self.is_too_small(package);
+        let dense = self.is_dense(package);
+        let light = self.is_light(package);
+        let category = match (bulky, heavy, package.is_perishable()) {
+            _ if overweight => SortCategory::Rejected,
+            (true, true, _) => SortCategory::Rejected,
+            (_, _, true) => SortCategory::Perishable,
+            (true, false, false) | (false, true, false) => SortCategory::Special,
+            (false, false, false) if too_small || dense => SortCategory::Special,
+            (false, false, false) => SortCategory::Standard,
+        };
+
+        let suspect = self.check_plausibility(package);
+
+        SortDecision {
+            category,
+            bulky,
+            heavy,
+            oversize_axes: self.oversize_axes(package),
+            actual_weight: package.mass,
+            dimensional_weight: self.dimensional_weight(package),
+            chargeable_weight: self.chargeable_weight(package),
+            overweight,
+            dense,
+            light,
+            too_small,
+            temperature: package.perishable,
+            time_sensitive: package.is_perishable() && category != SortCategory::Rejected,
+            stackability: package.stackability(),
+            profile: self.profile.clone(),
+            rule_version: self.version(),
+            suspect,
+            predictions: Vec::new(),
+            needs_review: suspect.is_some() || light,
+        }
+    }
+}
+
+/// Sorts a package under the given rules and reports why it was routed
+/// where it was.
+///
+/// # Example
+/// ```
+/// use sorter::{sort_package, Centimeters, Kilograms, Package, SortCategory, SortRules};
+///
+/// let package = Package::new(
+///     Centimeters::new(100.0),
+///     Centimeters::new(100.0),
+///     Centimeters::new(100.0),
+///     Kilograms::new(25.0),
+/// );
+/// let decision = sort_package(&package, &SortRules::default());
+/// assert_eq!(decision.category, SortCategory::Rejected);
+/// ```
+pub fn sort_package<S: Sortable + ?Sized>(item: &S, rules: &SortRules) -> SortDecision {
+    rules.decide(item)
+}
+
+/// Sorts packages based on their dimensions and mass.
+///
+/// Prefer [`sort_package`], which takes typed measurements and configurable
+/// rules and explains its decision.
+///
+/// # Arguments
+/// * `width` - Width in centimeters
+/// * `height` - Height in centimeters
+/// * `length` - Length in centimeters
+/// * `mass` - Mass in kilograms
+///
+/// # Returns
+/// * A string representing the stack name: "STANDARD", "SPECIAL", or "REJECTED"
+///
+/// # Rules
+/// * A package is **bulky** if:
+///   - Its volume (width × height × length) >= 1,000,000 cm³, OR
+///   - Any dimension >= 150 cm
+/// * A package is **heavy** if:
+///   - Its mass >= 20 kg
+///
+/// # Example
+/// ```
+/// # #![allow(deprecated)]
+/// use sorter::sort;
+///
+/// let result = sort(100.0, 100.0, 100.0, 25.0);
+/// assert_eq!(result, "REJECTED");
+/// ```
+#[deprecated(note = "use `sort_package` with `SortRules::default()` instead")]
+pub fn sort(width: f64, height: f64, length: f64, mass: f64) -> &'static str {
+    let package = Package::new(
+        Centimeters::new(width),
+        Centimeters::new(height),
+        Centimeters::new(length),
+        Kilograms::new(mass),
+    );
+    sort_package(&package, &SortRules::default())
+        .category
+        .as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_standard_package() {
+        // Small dimensions, light weight
+        assert_eq!(sort(10.0, 10.0, 10.0, 5.0), "STANDARD");
+        assert_eq!(sort(50.0, 50.0, 50.0, 10.0), "STANDARD");
+        // Just under all thresholds
+        assert_eq!(sort(149.0, 149.0, 1.0, 19.9), "STANDARD");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_bulky_by_volume() {
+        // Volume >= 1,000,000 cm³
+        assert_eq!(sort(100.0, 100.0, 100.0, 10.0), "SPECIAL");
+        assert_eq!(sort(200.0, 100.0, 50.0, 15.0), "SPECIAL");
+        // Exactly at threshold
+        assert_eq!(sort(100.0, 100.0, 100.0, 5.0), "SPECIAL");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_bulky_by_dimension() {
+        // One dimension >= 150 cm
+        assert_eq!(sort(150.0, 10.0, 10.0, 10.0), "SPECIAL");
+        assert_eq!(sort(10.0, 150.0, 10.0, 10.0), "SPECIAL");
+        assert_eq!(sort(10.0, 10.0, 150.0, 10.0), "SPECIAL");
+        assert_eq!(sort(200.0, 50.0, 50.0, 15.0), "SPECIAL");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_heavy_package() {
+        // Mass >= 20 kg, not bulky
+        assert_eq!(sort(10.0, 10.0, 10.0, 20.0), "SPECIAL");
+        assert_eq!(sort(50.0, 50.0, 50.0, 25.0), "SPECIAL");
+        assert_eq!(sort(50.0, 50.0, 50.0, 100.0), "SPECIAL");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_rejected_package() {
+        // Both bulky (by volume) and heavy
+        assert_eq!(sort(100.0, 100.0, 100.0, 20.0), "REJECTED");
+        assert_eq!(sort(200.0, 100.0, 50.0, 25.0), "REJECTED");
+
+        // Both bulky (by dimension) and heavy
+        assert_eq!(sort(150.0, 50.0, 50.0, 20.0), "REJECTED");
+        assert_eq!(sort(200.0, 10.0, 10.0, 30.0), "REJECTED");
+    }
+
+    #[test]
+    fn test_package_struct() {
+        // Test using the Package struct directly
+        let pkg = Package::new(
+            Centimeters::new(100.0),
+            Centimeters::new(100.0),
+            Centimeters::new(100.0),
+            Kilograms::new(25.0),
+        );
+
+        assert_eq!(pkg.volume(), 1_000_000.0);
+        assert!(pkg.is_bulky());
+        assert!(pkg.is_heavy());
+        assert_eq!(pkg.sort_category(), SortCategory::Rejected);
+    }
+
+    #[test]
+    fn test_newtype_safety() {
+        // Demonstrate type safety with newtypes
+        let width = Centimeters::new(150.0);
+        let mass = Kilograms::new(25.0);
+
+        assert_eq!(width.value(), 150.0);
+        assert_eq!(mass.value(), 25.0);
+
+        // The types prevent mixing up dimensions and mass at compile time
+    }
+
+    #[test]
+    fn test_normalized_package() {
+        let pkg = Package::new(
+            Centimeters::new(30.0),
+            Centimeters::new(10.0),
+            Centimeters::new(20.0),
+            Kilograms::new(5.0),
+        );
+        let normalized = pkg.normalized();
+
+        assert_eq!(normalized.length, Centimeters::new(30.0));
+        assert_eq!(normalized.width, Centimeters::new(20.0));
+        assert_eq!(normalized.height, Centimeters::new(10.0));
+        assert_eq!(normalized.volume(), pkg.volume());
+
+        // The same box entered in a different orientation compares equal
+        let rotated = Package::new(
+            Centimeters::new(10.0),
+            Centimeters::new(20.0),
+            Centimeters::new(30.0),
+            Kilograms::new(5.0),
+        );
+        assert_ne!(pkg, rotated);
+        assert_eq!(normalized, rotated.normalized());
+    }
+
+    #[test]
+    fn test_cylinder_package() {
+        // As a 110 x 110 x 100 box this tube would be bulky by volume
+        let tube = Package::cylinder(
+            Centimeters::new(110.0),
+            Centimeters::new(100.0),
+            Kilograms::new(10.0),
+        );
+        assert_eq!(tube.width, Centimeters::new(110.0));
+        assert!((tube.volume() - 950_331.8).abs() < 0.1);
+        assert_eq!(tube.sort_category(), SortCategory::Standard);
+
+        // A rolled carpet is still bulky by its length
+        let carpet = Package::cylinder(
+            Centimeters::new(30.0),
+            Centimeters::new(300.0),
+            Kilograms::new(15.0),
+        );
+        assert_eq!(carpet.sort_category(), SortCategory::Special);
+    }
+
+    #[test]
+    fn test_irregular_package() {
+        let pkg = Package::irregular(
+            [
+                Centimeters::new(100.0),
+                Centimeters::new(100.0),
+                Centimeters::new(100.0),
+            ],
+            Kilograms::new(5.0),
+        );
+        assert_eq!(pkg.volume(), 1_000_000.0);
+        assert!(pkg.is_bulky());
+    }
+
+    #[test]
+    fn test_fits_through_aperture() {
+        let gate = Aperture::new(Centimeters::new(140.0), Centimeters::new(120.0));
+
+        // Fits once turned so the 130 cm side goes through the 140 cm width
+        let pkg = Package::new(
+            Centimeters::new(110.0),
+            Centimeters::new(130.0),
+            Centimeters::new(145.0),
+            Kilograms::new(10.0),
+        );
+        assert!(pkg.fits_through(&gate));
+
+        // A flat 145 cm square is under every per-axis limit but won't fit
+        let pkg = Package::new(
+            Centimeters::new(145.0),
+            Centimeters::new(145.0),
+            Centimeters::new(10.0),
+            Kilograms::new(10.0),
+        );
+        assert!(!pkg.fits_through(&gate));
+        assert!(!pkg.is_bulky());
+
+        // With the gate configured, it becomes bulky
+        let rules = SortRules {
+            aperture: Some(gate),
+            ..SortRules::default()
+        };
+        assert!(rules.is_bulky(&pkg));
+        assert_eq!(rules.decide(&pkg).category, SortCategory::Special);
+    }
+
+    #[test]
+    fn test_too_small() {
+        let envelope = Package::new(
+            Centimeters::new(30.0),
+            Centimeters::new(0.5),
+            Centimeters::new(22.0),
+            Kilograms::new(0.1),
+        );
+        assert!(!envelope.decide().too_small);
+
+        let rules = SortRules {
+            min_dimension: Some(Centimeters::new(2.0)),
+            ..SortRules::default()
+        };
+        let decision = rules.decide(&envelope);
+        assert!(decision.too_small);
+        assert_eq!(decision.category, SortCategory::Special);
+        assert_eq!(decision.reasons(), vec![Reason::TooSmall]);
+    }
+
+    #[test]
+    fn test_max_mass() {
+        // Compact but too heavy to lift safely
+        let pkg = Package::new(
+            Centimeters::new(40.0),
+            Centimeters::new(40.0),
+            Centimeters::new(40.0),
+            Kilograms::new(75.0),
+        );
+        assert_eq!(pkg.sort_category(), SortCategory::Special);
+
+        let rules = SortRules {
+            max_mass: Some(Kilograms::new(70.0)),
+            ..SortRules::default()
+        };
+        let decision = rules.decide(&pkg);
+        assert_eq!(decision.category, SortCategory::Rejected);
+        assert_eq!(decision.reasons(), vec![Reason::Heavy, Reason::Overweight]);
+
+        let perishable =
+            pkg.with_temperature_range(TemperatureRange::new(Celsius::new(2.0), Celsius::new(8.0)));
+        assert_eq!(rules.decide(&perishable).category, SortCategory::Rejected);
+    }
+
+    #[test]
+    fn test_density_bands() {
+        let rules = SortRules {
+            dense_threshold: Some(2_000.0),
+            light_threshold: Some(20.0),
+            ..SortRules::default()
+        };
+
+        // 10 x 10 x 20 cm at 5 kg: 2,500 kg/m³, far under the heavy threshold
+        let ingot = Package::new(
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Centimeters::new(20.0),
+            Kilograms::new(5.0),
+        );
+        let decision = rules.decide(&ingot);
+        assert!(decision.dense);
+        assert_eq!(decision.category, SortCategory::Special);
+        assert_eq!(decision.reasons(), vec![Reason::Dense]);
+        assert_eq!(ingot.decide().category, SortCategory::Standard);
+
+        // 100 x 50 x 50 cm at 3 kg: 12 kg/m³, plausible but suspiciously light
+        let empty = Package::new(
+            Centimeters::new(100.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Kilograms::new(3.0),
+        );
+        let decision = rules.decide(&empty);
+        assert!(decision.light);
+        assert!(decision.suspect.is_none());
+        assert!(decision.needs_review);
+        assert_eq!(decision.category, SortCategory::Standard);
+    }
+
+    #[test]
+    fn test_sortable() {
+        struct Parcel {
+            dims_mm: [f64; 3],
+            grams: f64,
+        }
+
+        impl Sortable for Parcel {
+            fn dims(&self) -> [Centimeters; 3] {
+                self.dims_mm.map(|mm| Centimeters::new(mm / 10.0))
+            }
+
+            fn mass(&self) -> Kilograms {
+                Kilograms::new(self.grams / 1_000.0)
+            }
+        }
+
+        let parcel = Parcel {
+            dims_mm: [1_600.0, 500.0, 500.0],
+            grams: 25_000.0,
+        };
+        let rules = SortRules::default();
+        assert!(rules.is_bulky(&parcel));
+        assert!(rules.is_heavy(&parcel));
+        assert_eq!(
+            sort_package(&parcel, &rules).category,
+            SortCategory::Rejected
+        );
+    }
+
+    #[test]
+    fn test_axis_limits() {
+        let pkg = Package::new(
+            Centimeters::new(50.0),
+            Centimeters::new(40.0),
+            Centimeters::new(180.0),
+            Kilograms::new(10.0),
+        );
+        assert_eq!(pkg.decide().oversize_axes, vec![Axis::Length]);
+
+        // A long conveyor tolerates longer packages, but is narrower
+        let rules = SortRules {
+            axis_limits: Some(AxisLimits::new(
+                Centimeters::new(200.0),
+                Centimeters::new(45.0),
+                Centimeters::new(100.0),
+            )),
+            ..SortRules::default()
+        };
+        let decision = rules.decide(&pkg);
+        assert_eq!(decision.oversize_axes, vec![Axis::Width]);
+        assert_eq!(decision.category, SortCategory::Special);
+
+        let narrow = Package::new(
+            Centimeters::new(40.0),
+            Centimeters::new(30.0),
+            Centimeters::new(180.0),
+            Kilograms::new(10.0),
+        );
+        assert!(rules.decide(&narrow).oversize_axes.is_empty());
+        assert_eq!(rules.decide(&narrow).category, SortCategory::Standard);
+    }
+
+    #[test]
+    fn test_package_comparisons() {
+        // 50 x 40 x 30 = 60,000 cm³ -> 12 kg dimensional weight
+        let light = Package::new(
+            Centimeters::new(50.0),
+            Centimeters::new(40.0),
+            Centimeters::new(30.0),
+            Kilograms::new(5.0),
+        );
+        let dense = Package::new(
+            Centimeters::new(30.0),
+            Centimeters::new(20.0),
+            Centimeters::new(20.0),
+            Kilograms::new(15.0),
+        );
+        assert_eq!(light.dimensional_weight(), Kilograms::new(12.0));
+        assert_eq!(light.chargeable_weight(), Kilograms::new(12.0));
+        assert_eq!(dense.chargeable_weight(), Kilograms::new(15.0));
+
+        // Billing uses the active rules' divisor
+        let rules = SortRules {
+            dim_divisor: 6_000.0,
+            ..SortRules::default()
+        };
+        let decision = rules.decide(&light);
+        assert_eq!(decision.actual_weight, Kilograms::new(5.0));
+        assert_eq!(decision.dimensional_weight, Kilograms::new(10.0));
+        assert_eq!(decision.chargeable_weight, Kilograms::new(10.0));
+
+        assert_eq!(light.cmp_by_volume(&dense), Ordering::Greater);
+        assert_eq!(light.cmp_by_chargeable_weight(&dense), Ordering::Less);
+
+        // Orientation doesn't matter, but every measure must be at least as large
+        let bigger = Package::new(
+            Centimeters::new(30.0),
+            Centimeters::new(50.0),
+            Centimeters::new(40.0),
+            Kilograms::new(6.0),
+        );
+        assert!(bigger.dominates(&light));
+        assert!(light.dominates(&light));
+        assert!(!light.dominates(&bigger));
+        assert!(!bigger.dominates(&dense));
+    }
+
+    #[test]
+    fn test_stackability() {
+        // Dense with a large footprint: good bottom layer
+        let pkg = Package::new(
+            Centimeters::new(100.0),
+            Centimeters::new(20.0),
+            Centimeters::new(60.0),
+            Kilograms::new(60.0),
+        );
+        assert_eq!(pkg.density(), 500.0);
+        assert_eq!(pkg.stackability(), 100);
+        assert!(pkg.decide().can_be_bottom_layer());
+
+        // Light and small: goes on top
+        let pkg = Package::new(
+            Centimeters::new(20.0),
+            Centimeters::new(20.0),
+            Centimeters::new(20.0),
+            Kilograms::new(0.4),
+        );
+        assert_eq!(pkg.stackability(), 9);
+        assert!(!pkg.decide().can_be_bottom_layer());
+
+        // Fragile packages never bear load
+        let pkg = pkg.with_fragile();
+        assert_eq!(pkg.decide().stackability, 0);
+    }
+
+    #[test]
+    fn test_decision_reasons() {
+        let pkg = Package::new(
+            Centimeters::new(160.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Kilograms::new(25.0),
+        );
+        assert_eq!(pkg.decide().reasons(), vec![Reason::Bulky, Reason::Heavy]);
+
+        let pkg = Package::new(
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Kilograms::new(1.0),
+        );
+        assert!(pkg.decide().reasons().is_empty());
+    }
+
+    #[test]
+    fn test_time_based_override() {
+        use schedule::{Date, Schedule};
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let rules = SortRules {
+            schedule: vec![RuleOverride {
+                name: "peak".to_string(),
+                schedule: Schedule {
+                    dates: Some((
+                        Date::new(2024, 11, 25).unwrap(),
+                        Date::new(2024, 12, 24).unwrap(),
+                    )),
+                    hours: None,
+                },
+                mass_threshold: Some(Kilograms::new(15.0)),
+                ..RuleOverride::default()
+            }],
+            ..SortRules::default()
+        };
+        let pkg = Package::new(
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Kilograms::new(16.0),
+        );
+
+        // 2024-11-29T14:30:00Z is in peak season
+        let peak = rules.at(UNIX_EPOCH + Duration::from_secs(1_732_890_600));
+        let decision = peak.decide(&pkg);
+        assert_eq!(decision.category, SortCategory::Special);
+        assert_eq!(decision.rule_version, "base+peak");
+
+        let decision = rules.at(UNIX_EPOCH).decide(&pkg);
+        assert_eq!(decision.category, SortCategory::Standard);
+        assert_eq!(decision.rule_version, "base");
+    }
+
+    #[test]
+    fn test_suspect_measurements() {
+        let rules = SortRules::default();
+        let pkg = |w: f64, h: f64, l: f64, m: f64| {
+            Package::new(
+                Centimeters::new(w),
+                Centimeters::new(h),
+                Centimeters::new(l),
+                Kilograms::new(m),
+            )
+        };
+
+        // 0.5 kg in a 900,000 cm³ box
+        let decision = rules.decide(&pkg(100.0, 90.0, 100.0, 0.5));
+        assert_eq!(decision.suspect, Some(SuspectMeasurement::TooLight));
+        assert!(decision.needs_review);
+
+        // 100 kg in a shoebox
+        let decision = rules.decide(&pkg(30.0, 20.0, 10.0, 100.0));
+        assert_eq!(decision.suspect, Some(SuspectMeasurement::TooDense));
+        assert_eq!(decision.category, SortCategory::Special);
+
+        // Zero dimension with a large mass
+        let decision = rules.decide(&pkg(0.0, 20.0, 10.0, 15.0));
+        assert_eq!(decision.suspect, Some(SuspectMeasurement::NonPhysical));
+
+        let decision = rules.decide(&pkg(30.0, 20.0, 10.0, f64::NAN));
+        assert_eq!(decision.suspect, Some(SuspectMeasurement::NonPhysical));
+
+        // Ordinary packages pass, and the check can be disabled
+        assert_eq!(rules.decide(&pkg(50.0, 50.0, 50.0, 10.0)).suspect, None);
+        let lenient = SortRules {
+            plausible_density: None,
+            ..SortRules::default()
+        };
+        assert_eq!(lenient.decide(&pkg(30.0, 20.0, 10.0, 100.0)).suspect, None);
+    }
+
+    #[test]
+    fn test_perishable_package() {
+        let chilled = TemperatureRange::new(Celsius::new(2.0), Celsius::new(8.0));
+        assert!(chilled.contains(Celsius::new(4.0)));
+        assert!(!chilled.contains(Celsius::new(-1.0)));
+
+        // Perishable packages go to their own lane, even when bulky or heavy
+        let pkg = Package::new(
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Kilograms::new(25.0),
+        )
+        .with_temperature_range(chilled);
+
+        let decision = pkg.decide();
+        assert_eq!(decision.category, SortCategory::Perishable);
+        assert_eq!(decision.temperature, Some(chilled));
+        assert!(decision.heavy);
+        assert!(decision.time_sensitive);
+    }
+
+    #[test]
+    fn test_perishable_rejected_package() {
+        // Bulky and heavy packages are rejected regardless of perishability
+        let pkg = Package::new(
+            Centimeters::new(160.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Kilograms::new(25.0),
+        )
+        .with_temperature_range(TemperatureRange::new(
+            Celsius::new(-20.0),
+            Celsius::new(-15.0),
+        ));
+
+        let decision = pkg.decide();
+        assert_eq!(decision.category, SortCategory::Rejected);
+        assert!(!decision.time_sensitive);
+    }
+}
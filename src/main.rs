@@ -1,238 +1,368 @@
+use std::env;
 use std::fmt;
+use std::path::Path;
+use std::process;
+use std::time::SystemTime;
+
+use sorter::audit;
+use sorter::batch;
+use sorter::i18n::{Locale, Message};
+use sorter::numbers::ParseMode;
+use sorter::record::{DecisionRecord, OutputFormat};
+use sorter::review::{Resolution, ReviewQueue};
+use sorter::uncertainty::decide_with_uncertainty;
+use sorter::{
+    bench, selftest, Centimeters, Config, Kilograms, Package, SortCategory, SortDecision,
+};
 
-/// Represents a dimension in centimeters (newtype pattern for type safety)
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
-pub struct Centimeters(f64);
-
-impl Centimeters {
-    pub fn new(value: f64) -> Self {
-        Self(value)
-    }
-
-    pub fn value(&self) -> f64 {
-        self.0
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (config, command) = match Config::load(&args, env::vars()) {
+        Ok(loaded) => loaded,
+        // The configured locale isn't known yet
+        Err(err) => fail(Locale::default(), err),
+    };
+
+    let command: Vec<&str> = command.iter().map(String::as_str).collect();
+    match command.as_slice() {
+        [] => run_examples(&config),
+        ["config", "show"] => print!("{}", config),
+        ["selftest"] => run_selftest(&config),
+        ["replay", log] => run_replay(&config, Path::new(log)),
+        ["review", queue, action @ ..] => run_review(&config, Path::new(queue), action),
+        ["batch", csv] => run_batch(&config, Path::new(csv), None),
+        ["batch", csv, errors] => run_batch(&config, Path::new(csv), Some(Path::new(errors))),
+        ["bench", options @ ..] if options.len() <= 2 => {
+            let count = options.first().map_or(Some(bench::DEFAULT_PACKAGES), |n| {
+                n.parse().ok().filter(|&n| n > 0)
+            });
+            let seed = options
+                .get(1)
+                .map_or(Some(bench::DEFAULT_SEED), |seed| seed.parse().ok());
+            match (count, seed) {
+                (Some(count), Some(seed)) => run_bench(&config, count, seed),
+                _ => usage(config.locale),
+            }
+        }
+        _ => usage(config.locale),
     }
 }
 
-/// Represents mass in kilograms (newtype pattern for type safety)
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
-pub struct Kilograms(f64);
+fn usage(locale: Locale) -> ! {
+    eprintln!(
+        "{}",
+        locale.format(
+            Message::Usage,
+            &[&"sorter [--config <file>] [--<setting> <value>]... [config show | selftest | replay <log> | review <queue> <action> | batch <csv> [<errors>] | bench [N] [SEED]]"]
+        )
+    );
+    eprintln!(
+        "{}",
+        locale.format(
+            Message::ReviewActions,
+            &[&"list | add <log> | approve <id> | reclassify <id> <CATEGORY>"]
+        )
+    );
+    process::exit(2);
+}
 
-impl Kilograms {
-    pub fn new(value: f64) -> Self {
-        Self(value)
+/// Re-sorts the current decisions of a saved audit log under the configured
+/// rules and prints the packages whose category would change, as text or as
+/// one JSON object per change
+fn run_replay(config: &Config, path: &Path) {
+    let log = audit::load_log(path).unwrap_or_else(|err| fail(config.locale, err));
+    let changes = audit::replay_log(&log, &config.active_rules().unwrap_or_default());
+    if config.output == OutputFormat::Json {
+        for change in &changes {
+            println!("{}", change.to_json());
+        }
+        return;
     }
-
-    pub fn value(&self) -> f64 {
-        self.0
+    for change in &changes {
+        println!(
+            "{}: {} -> {}",
+            change.package_id,
+            config.category_label(change.before),
+            config.category_label(change.after)
+        );
     }
+    let replayed = log.iter().filter(|logged| logged.current).count();
+    println!(
+        "{}",
+        config
+            .locale
+            .format(Message::CategoryChanges, &[&changes.len(), &replayed])
+    );
 }
 
-/// Package sorting category
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SortCategory {
-    /// Standard packages (not bulky or heavy) - can be handled normally
-    Standard,
-    /// Special packages (either bulky or heavy) - require special handling
-    Special,
-    /// Rejected packages (both bulky and heavy) - cannot be processed
-    Rejected,
-}
-
-impl fmt::Display for SortCategory {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.as_str())
+/// Works through a saved review queue: lists the pending items, queues the
+/// current decisions of a saved audit log that need review, or resolves an
+/// item. A missing queue file is an empty queue. JSON output has one queue
+/// item per line, and no summary.
+fn run_review(config: &Config, path: &Path, action: &[&str]) {
+    let mut queue = if path.exists() {
+        ReviewQueue::load(path).unwrap_or_else(|err| fail(config.locale, err))
+    } else {
+        ReviewQueue::new()
+    };
+    let json = config.output == OutputFormat::Json;
+
+    match action {
+        ["list"] if json => {
+            for item in queue.pending() {
+                println!("{}", item.to_json());
+            }
+            return;
+        }
+        ["list"] => {
+            for item in queue.pending() {
+                let reasons: Vec<&str> = item.reasons.iter().map(|r| r.as_str()).collect();
+                println!(
+                    "{}: {} ({})",
+                    item.package_id,
+                    config.category_label(item.category),
+                    reasons.join(", ")
+                );
+            }
+            println!(
+                "{}",
+                config.locale.format(
+                    Message::ReviewSummary,
+                    &[&queue.pending().len(), &queue.resolved().len()]
+                )
+            );
+            return;
+        }
+        ["add", log] => {
+            let log =
+                audit::load_log(Path::new(log)).unwrap_or_else(|err| fail(config.locale, err));
+            let rules = config.active_rules().unwrap_or_default();
+            let current: Vec<_> = log.iter().filter(|logged| logged.current).collect();
+            let mut queued = 0;
+            for logged in &current {
+                // Packages already in the queue, pending or reviewed, aren't
+                // queued again
+                let known = queue
+                    .pending()
+                    .iter()
+                    .any(|item| item.package_id == logged.package_id)
+                    || queue
+                        .resolved()
+                        .iter()
+                        .any(|r| r.item.package_id == logged.package_id);
+                if known {
+                    continue;
+                }
+                // As of when the package was sorted, like `replay`
+                let rules = rules.at(logged.recorded_at);
+                let decision =
+                    decide_with_uncertainty(&rules, &logged.package, &config.uncertainty);
+                if queue.submit_uncertain(logged.package_id.as_str(), decision) {
+                    queued += 1;
+                    if json {
+                        println!("{}", queue.pending()[queue.pending().len() - 1].to_json());
+                    }
+                }
+            }
+            if !json {
+                println!(
+                    "{}",
+                    config
+                        .locale
+                        .format(Message::Queued, &[&queued, &current.len()])
+                );
+            }
+        }
+        ["approve", id] => {
+            let resolved = queue
+                .approve(id)
+                .unwrap_or_else(|err| fail(config.locale, err));
+            if json {
+                println!("{}", resolved.to_json());
+            } else {
+                println!(
+                    "{}",
+                    config.locale.format(
+                        Message::Approved,
+                        &[id, &config.category_label(resolved.item.category)]
+                    )
+                );
+            }
+        }
+        ["reclassify", id, category] => {
+            let category: SortCategory = category
+                .parse()
+                .unwrap_or_else(|err| fail(config.locale, err));
+            let resolved = queue
+                .reclassify(id, category)
+                .unwrap_or_else(|err| fail(config.locale, err));
+            let from = match resolved.resolution {
+                Resolution::Reclassified { from } => from,
+                Resolution::Approved => category,
+            };
+            if json {
+                println!("{}", resolved.to_json());
+            } else {
+                println!(
+                    "{}: {} -> {}",
+                    id,
+                    config.category_label(from),
+                    config.category_label(category)
+                );
+            }
+        }
+        _ => usage(config.locale),
     }
+    queue
+        .save(path)
+        .unwrap_or_else(|err| fail(config.locale, err));
 }
 
-impl SortCategory {
-    /// Returns the string representation of the category
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            SortCategory::Standard => "STANDARD",
-            SortCategory::Special => "SPECIAL",
-            SortCategory::Rejected => "REJECTED",
+/// Sorts the packages of a CSV batch file under the configured rules,
+/// reading numbers in the configured format. Malformed rows are reported on
+/// stderr and, if `errors` is given, saved there as JSON lines.
+fn run_batch(config: &Config, path: &Path, errors: Option<&Path>) {
+    let locale = config.locale;
+    let mode = ParseMode::Format(config.number_format());
+    let batch =
+        batch::load_csv(path, &config.columns, mode).unwrap_or_else(|err| fail(locale, err));
+    let rules = config
+        .active_rules()
+        .unwrap_or_default()
+        .at(SystemTime::now());
+
+    for (line, package) in &batch.packages {
+        let decision = rules.decide(package);
+        if config.output == OutputFormat::Json {
+            let record = DecisionRecord::new(None, package, &decision).with_labels(&config.labels);
+            println!("{}", record);
+        } else {
+            println!(
+                "{}",
+                locale.format(
+                    Message::RowError,
+                    &[line, &describe(config, package, &decision)]
+                )
+            );
         }
     }
-}
-
-/// Represents a package with dimensions and mass
-#[derive(Debug, Clone, Copy)]
-pub struct Package {
-    pub width: Centimeters,
-    pub height: Centimeters,
-    pub length: Centimeters,
-    pub mass: Kilograms,
-}
-
-impl Package {
-    /// Creates a new package with the given dimensions and mass
-    pub fn new(width: Centimeters, height: Centimeters, length: Centimeters, mass: Kilograms) -> Self {
-        Self { width, height, length, mass }
+    for error in &batch.errors {
+        eprintln!(
+            "{}",
+            locale.format(Message::RowError, &[&error.line, &error.reason])
+        );
     }
-
-    /// Calculates the volume of the package in cubic centimeters
-    pub fn volume(&self) -> f64 {
-        self.width.value() * self.height.value() * self.length.value()
+    if let Some(errors) = errors {
+        batch
+            .save_errors(errors)
+            .unwrap_or_else(|err| fail(locale, err));
     }
-
-    /// Checks if the package is bulky according to the rules:
-    /// - Volume >= 1,000,000 cm³, OR
-    /// - Any dimension >= 150 cm
-    pub fn is_bulky(&self) -> bool {
-        const VOLUME_THRESHOLD: f64 = 1_000_000.0;
-        const DIMENSION_THRESHOLD: f64 = 150.0;
-
-        self.volume() >= VOLUME_THRESHOLD
-            || self.width.value() >= DIMENSION_THRESHOLD
-            || self.height.value() >= DIMENSION_THRESHOLD
-            || self.length.value() >= DIMENSION_THRESHOLD
+    if config.output == OutputFormat::Text {
+        println!(
+            "{}",
+            locale.format(
+                Message::BatchSummary,
+                &[&batch.rows(), &batch.packages.len(), &batch.errors.len()]
+            )
+        );
     }
+}
 
-    /// Checks if the package is heavy (mass >= 20 kg)
-    pub fn is_heavy(&self) -> bool {
-        const MASS_THRESHOLD: f64 = 20.0;
-        self.mass.value() >= MASS_THRESHOLD
-    }
+/// Prints the error and exits with status 2
+fn fail(locale: Locale, err: impl fmt::Display) -> ! {
+    eprintln!("{}", locale.format(Message::Error, &[&err]));
+    process::exit(2);
+}
 
-    /// Determines the sort category for this package
-    pub fn sort_category(&self) -> SortCategory {
-        match (self.is_bulky(), self.is_heavy()) {
-            (true, true) => SortCategory::Rejected,
-            (true, false) | (false, true) => SortCategory::Special,
-            (false, false) => SortCategory::Standard,
-        }
+/// Checks the configured rules against the built-in boundary cases, exiting
+/// with status 1 if any fails
+fn run_selftest(config: &Config) {
+    let rules = config
+        .active_rules()
+        .unwrap_or_default()
+        .at(SystemTime::now());
+    let report = selftest::run(&rules);
+    println!("{}", report);
+    if !report.passed() {
+        process::exit(1);
     }
 }
 
-/// Sorts packages based on their dimensions and mass.
-///
-/// # Arguments
-/// * `width` - Width in centimeters
-/// * `height` - Height in centimeters
-/// * `length` - Length in centimeters
-/// * `mass` - Mass in kilograms
-///
-/// # Returns
-/// * A string representing the stack name: "STANDARD", "SPECIAL", or "REJECTED"
-///
-/// # Rules
-/// * A package is **bulky** if:
-///   - Its volume (width × height × length) >= 1,000,000 cm³, OR
-///   - Any dimension >= 150 cm
-/// * A package is **heavy** if:
-///   - Its mass >= 20 kg
-///
-/// # Example
-/// ```
-/// let result = sort(100.0, 100.0, 100.0, 25.0);
-/// assert_eq!(result, "REJECTED");
-/// ```
-pub fn sort(width: f64, height: f64, length: f64, mass: f64) -> &'static str {
-    let package = Package::new(
-        Centimeters::new(width),
-        Centimeters::new(height),
-        Centimeters::new(length),
-        Kilograms::new(mass),
-    );
-    package.sort_category().as_str()
+/// Benchmarks classification of `count` synthetic packages under the
+/// configured rules and prints the throughput
+fn run_bench(config: &Config, count: usize, seed: u64) {
+    let rules = config
+        .active_rules()
+        .unwrap_or_default()
+        .at(SystemTime::now());
+    println!("{}", bench::run(count, seed, &rules));
 }
 
-fn main() {
-    println!("Package Sorting System\n");
+/// Sorts the example packages under the configured rules and prints the
+/// results, as text or as one JSON decision record per line
+fn run_examples(config: &Config) {
+    let locale = config.locale;
+    // `Config::load` has already checked the selected profile exists
+    let rules = config
+        .active_rules()
+        .unwrap_or_default()
+        .at(SystemTime::now());
+    if config.output == OutputFormat::Text {
+        println!("{}\n", locale.message(Message::Title));
+    }
 
     let test_cases = vec![
-        (50.0, 50.0, 50.0, 10.0, "Standard package"),
-        (100.0, 100.0, 100.0, 10.0, "Bulky by volume"),
-        (160.0, 50.0, 50.0, 10.0, "Bulky by dimension"),
-        (50.0, 50.0, 50.0, 25.0, "Heavy package"),
-        (160.0, 50.0, 50.0, 25.0, "Bulky and heavy"),
+        (50.0, 50.0, 50.0, 10.0, Message::StandardExample),
+        (100.0, 100.0, 100.0, 10.0, Message::BulkyByVolumeExample),
+        (160.0, 50.0, 50.0, 10.0, Message::BulkyByDimensionExample),
+        (50.0, 50.0, 50.0, 25.0, Message::HeavyExample),
+        (160.0, 50.0, 50.0, 25.0, Message::BulkyAndHeavyExample),
     ];
 
-    for (width, height, length, mass, description) in test_cases {
-        let category = sort(width, height, length, mass);
-        println!(
-            "{}: {}x{}x{} cm, {} kg -> {}",
-            description, width, height, length, mass, category
+    for (width, height, length, mass, example) in test_cases {
+        let package = Package::new(
+            Centimeters::new(width),
+            Centimeters::new(height),
+            Centimeters::new(length),
+            Kilograms::new(mass),
         );
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_standard_package() {
-        // Small dimensions, light weight
-        assert_eq!(sort(10.0, 10.0, 10.0, 5.0), "STANDARD");
-        assert_eq!(sort(50.0, 50.0, 50.0, 10.0), "STANDARD");
-        // Just under all thresholds
-        assert_eq!(sort(149.0, 149.0, 1.0, 19.9), "STANDARD");
-    }
-
-    #[test]
-    fn test_bulky_by_volume() {
-        // Volume >= 1,000,000 cm³
-        assert_eq!(sort(100.0, 100.0, 100.0, 10.0), "SPECIAL");
-        assert_eq!(sort(200.0, 100.0, 50.0, 15.0), "SPECIAL");
-        // Exactly at threshold
-        assert_eq!(sort(100.0, 100.0, 100.0, 5.0), "SPECIAL");
-    }
-
-    #[test]
-    fn test_bulky_by_dimension() {
-        // One dimension >= 150 cm
-        assert_eq!(sort(150.0, 10.0, 10.0, 10.0), "SPECIAL");
-        assert_eq!(sort(10.0, 150.0, 10.0, 10.0), "SPECIAL");
-        assert_eq!(sort(10.0, 10.0, 150.0, 10.0), "SPECIAL");
-        assert_eq!(sort(200.0, 50.0, 50.0, 15.0), "SPECIAL");
-    }
-
-    #[test]
-    fn test_heavy_package() {
-        // Mass >= 20 kg, not bulky
-        assert_eq!(sort(10.0, 10.0, 10.0, 20.0), "SPECIAL");
-        assert_eq!(sort(50.0, 50.0, 50.0, 25.0), "SPECIAL");
-        assert_eq!(sort(50.0, 50.0, 50.0, 100.0), "SPECIAL");
-    }
-
-    #[test]
-    fn test_rejected_package() {
-        // Both bulky (by volume) and heavy
-        assert_eq!(sort(100.0, 100.0, 100.0, 20.0), "REJECTED");
-        assert_eq!(sort(200.0, 100.0, 50.0, 25.0), "REJECTED");
-
-        // Both bulky (by dimension) and heavy
-        assert_eq!(sort(150.0, 50.0, 50.0, 20.0), "REJECTED");
-        assert_eq!(sort(200.0, 10.0, 10.0, 30.0), "REJECTED");
-    }
+        let decision = rules.decide(&package);
+        if config.output == OutputFormat::Json {
+            let record = DecisionRecord::new(None, &package, &decision).with_labels(&config.labels);
+            println!("{}", record);
+            continue;
+        }
 
-    #[test]
-    fn test_package_struct() {
-        // Test using the Package struct directly
-        let pkg = Package::new(
-            Centimeters::new(100.0),
-            Centimeters::new(100.0),
-            Centimeters::new(100.0),
-            Kilograms::new(25.0),
+        println!(
+            "{}: {}",
+            locale.message(example),
+            describe(config, &package, &decision)
         );
-
-        assert_eq!(pkg.volume(), 1_000_000.0);
-        assert!(pkg.is_bulky());
-        assert!(pkg.is_heavy());
-        assert_eq!(pkg.sort_category(), SortCategory::Rejected);
     }
+}
 
-    #[test]
-    fn test_newtype_safety() {
-        // Demonstrate type safety with newtypes
-        let width = Centimeters::new(150.0);
-        let mass = Kilograms::new(25.0);
-
-        assert_eq!(width.value(), 150.0);
-        assert_eq!(mass.value(), 25.0);
-
-        // The types prevent mixing up dimensions and mass at compile time
-    }
+/// Describes a package and its decision in the configured units and locale,
+/// e.g. `50 x 50 x 50 cm, 25 kg -> SPECIAL (heavy)`
+fn describe(config: &Config, package: &Package, decision: &SortDecision) -> String {
+    let locale = config.locale;
+    let format = config.number_format();
+    let reasons: Vec<&str> = decision
+        .reasons()
+        .into_iter()
+        .map(|reason| locale.reason(reason))
+        .collect();
+    let reasons = if reasons.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", reasons.join(", "))
+    };
+    format!(
+        "{}, {} -> {}{}",
+        config
+            .units
+            .dimensions_in(&[package.width, package.height, package.length], &format),
+        config.units.mass_in(package.mass, &format),
+        config.category_label(decision.category),
+        reasons
+    )
 }
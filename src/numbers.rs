@@ -0,0 +1,383 @@
+//! Parsing numeric measurements from text, with an opt-in lenient mode for
+//! dirty data, and locale-aware number formats.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// How forgiving number parsing is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Only plain numbers as accepted by `f64::from_str`
+    #[default]
+    Strict,
+    /// Also accepts surrounding whitespace, thousands separators, comma
+    /// decimals (`1.234,5`) and scientific notation, recording each
+    /// normalization performed
+    Lenient,
+    /// Numbers written in a known format, e.g. `1.234,5` at EU sites.
+    /// Unlike lenient mode nothing is guessed: `1,234` is 1.234 when the
+    /// decimal separator is a comma.
+    Format(NumberFormat),
+}
+
+/// A change made to a value so it could be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Leading or trailing whitespace was removed
+    Trimmed,
+    /// Thousands separators were removed
+    ThousandsSeparators,
+    /// A decimal comma was read as a decimal point
+    CommaDecimal,
+    /// The value was written in scientific notation
+    ScientificNotation,
+}
+
+/// A parsed number and the normalizations needed to parse it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedNumber {
+    pub value: f64,
+    pub normalizations: Vec<Normalization>,
+}
+
+/// Parses a numeric measurement.
+///
+/// In lenient mode, when both `.` and `,` appear the last one is the decimal
+/// separator and the other groups thousands. A separator that appears more
+/// than once groups thousands (`1.234.567`). A lone `.` is always a decimal
+/// point (`0.250`); only a lone `,` is ambiguous, grouping thousands when
+/// exactly three digits follow it (`1,234`) and a decimal separator otherwise
+/// (`12,5`). Thousands groups after the first must have exactly three digits.
+pub fn parse_number(text: &str, mode: ParseMode) -> Result<ParsedNumber, ParseNumberError> {
+    let error = || ParseNumberError(text.to_string());
+
+    match mode {
+        ParseMode::Strict => {
+            let value = text.parse().map_err(|_| error())?;
+            return Ok(ParsedNumber {
+                value,
+                normalizations: Vec::new(),
+            });
+        }
+        ParseMode::Format(format) => return format.parse_number(text).ok_or_else(error),
+        ParseMode::Lenient => {}
+    }
+
+    let mut normalizations = Vec::new();
+    let trimmed = text.trim();
+    if trimmed.len() != text.len() {
+        normalizations.push(Normalization::Trimmed);
+    }
+
+    let (mantissa, exponent) = match trimmed.find(['e', 'E']) {
+        Some(index) => {
+            normalizations.push(Normalization::ScientificNotation);
+            trimmed.split_at(index)
+        }
+        None => (trimmed, ""),
+    };
+
+    let format = match (mantissa.rfind('.'), mantissa.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => NumberFormat::COMMA,
+        (Some(_), None) if mantissa.matches('.').count() > 1 => NumberFormat::COMMA,
+        (None, Some(comma)) if mantissa.matches(',').count() == 1 => {
+            // A leading group of `0` or `0…` can't be thousands, so `0,250` is
+            // a comma decimal
+            let before = mantissa[..comma].trim_start_matches(['-', '+']);
+            let after = &mantissa[comma + 1..];
+            if !before.is_empty()
+                && !before.starts_with('0')
+                && after.len() == 3
+                && after.chars().all(|c| c.is_ascii_digit())
+            {
+                NumberFormat::POINT
+            } else {
+                NumberFormat::COMMA
+            }
+        }
+        _ => NumberFormat::POINT,
+    };
+
+    let (mut plain, format_normalizations) = format.normalize(mantissa).ok_or_else(error)?;
+    normalizations.extend(format_normalizations);
+    plain.push_str(exponent);
+
+    let value = plain.parse().map_err(|_| error())?;
+    Ok(ParsedNumber {
+        value,
+        normalizations,
+    })
+}
+
+/// How numbers are written: the decimal separator and the optional digit
+/// grouping separator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    pub decimal: char,
+    pub grouping: Option<char>,
+}
+
+impl Default for NumberFormat {
+    /// Plain numbers: decimal point, no grouping (`1234.5`)
+    fn default() -> Self {
+        Self::new('.', None)
+    }
+}
+
+impl NumberFormat {
+    /// Decimal point with comma grouping (`1,234.5`)
+    pub const POINT: NumberFormat = NumberFormat::new('.', Some(','));
+
+    /// Decimal comma with point grouping (`1.234,5`)
+    pub const COMMA: NumberFormat = NumberFormat::new(',', Some('.'));
+
+    pub const fn new(decimal: char, grouping: Option<char>) -> Self {
+        Self { decimal, grouping }
+    }
+
+    /// Parses a number written in this format. Grouping separators are
+    /// optional, but where present must separate groups of three digits.
+    pub fn parse(&self, text: &str) -> Result<f64, ParseNumberError> {
+        parse_number(text, ParseMode::Format(*self)).map(|parsed| parsed.value)
+    }
+
+    /// Formats a number in this format, with the given number of decimals or
+    /// (`None`) as few as needed
+    pub fn format(&self, value: f64, decimals: Option<usize>) -> String {
+        let plain = match decimals {
+            Some(decimals) => format!("{:.*}", decimals, value),
+            None => value.to_string(),
+        };
+        let (sign, unsigned) = match plain.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", plain.as_str()),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+        let mut formatted = String::from(sign);
+        for (index, digit) in integer.chars().enumerate() {
+            if index > 0 && (integer.len() - index) % 3 == 0 {
+                formatted.extend(self.grouping);
+            }
+            formatted.push(digit);
+        }
+        if !fraction.is_empty() {
+            formatted.push(self.decimal);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
+    fn parse_number(&self, text: &str) -> Option<ParsedNumber> {
+        let (plain, normalizations) = self.normalize(text)?;
+        Some(ParsedNumber {
+            value: plain.parse().ok()?,
+            normalizations,
+        })
+    }
+
+    /// Rewrites a number in this format as a plain number for
+    /// `f64::from_str`, checking that grouping separators split the integer
+    /// part into groups of three digits
+    fn normalize(&self, text: &str) -> Option<(String, Vec<Normalization>)> {
+        let mut normalizations = Vec::new();
+        let trimmed = text.trim();
+        if trimmed.len() != text.len() {
+            normalizations.push(Normalization::Trimmed);
+        }
+
+        let (integer, fraction) = match trimmed.rsplit_once(self.decimal) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (trimmed, None),
+        };
+        let digits = integer.trim_start_matches(['-', '+']);
+        let mut plain = integer[..integer.len() - digits.len()].to_string();
+        match self.grouping.filter(|&g| digits.contains(g)) {
+            Some(grouping) => {
+                let mut groups = digits.split(grouping);
+                let first = groups.next()?;
+                let valid = (1..=3).contains(&first.len())
+                    && !first.starts_with('0')
+                    && groups.all(|group| group.len() == 3)
+                    && digits.chars().all(|c| c == grouping || c.is_ascii_digit());
+                if !valid {
+                    return None;
+                }
+                plain.extend(digits.chars().filter(|&c| c != grouping));
+                normalizations.push(Normalization::ThousandsSeparators);
+            }
+            None => plain.push_str(digits),
+        }
+        if let Some(fraction) = fraction {
+            plain.push('.');
+            plain.push_str(fraction);
+            if self.decimal == ',' {
+                normalizations.push(Normalization::CommaDecimal);
+            }
+        }
+        Some((plain, normalizations))
+    }
+}
+
+impl fmt::Display for NumberFormat {
+    /// Shows the format by example, e.g. `1.234,5`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format(1234.5, Some(1)))
+    }
+}
+
+impl FromStr for NumberFormat {
+    type Err = ParseNumberFormatError;
+
+    /// Parses a format from how it writes 1234.5, e.g. `1.234,5`, `1 234,5`
+    /// or `1234.5`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseNumberFormatError(s.to_string());
+        let rest = s.strip_prefix('1').ok_or_else(error)?;
+        let (grouping, rest) = match rest.strip_prefix("234") {
+            Some(rest) => (None, rest),
+            None => {
+                let mut chars = rest.chars();
+                let grouping = chars.next().ok_or_else(error)?;
+                (
+                    Some(grouping),
+                    chars.as_str().strip_prefix("234").ok_or_else(error)?,
+                )
+            }
+        };
+        let decimal = rest.strip_suffix('5').ok_or_else(error)?;
+        let mut chars = decimal.chars();
+        let (Some(decimal), None) = (chars.next(), chars.next()) else {
+            return Err(error());
+        };
+        if decimal.is_ascii_digit() || grouping.is_some_and(|g| g == decimal || g.is_ascii_digit())
+        {
+            return Err(error());
+        }
+        Ok(Self::new(decimal, grouping))
+    }
+}
+
+/// Error returned when a number format isn't given as an example of 1234.5
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNumberFormatError(String);
+
+impl fmt::Display for ParseNumberFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid number format {:?}: expected how 1234.5 is written, e.g. 1.234,5",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseNumberFormatError {}
+
+/// Error returned when text isn't a number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNumberError(String);
+
+impl fmt::Display for ParseNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid number: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseNumberError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Normalization::*;
+
+    fn lenient(text: &str) -> (f64, Vec<Normalization>) {
+        let parsed = parse_number(text, ParseMode::Lenient).unwrap();
+        (parsed.value, parsed.normalizations)
+    }
+
+    #[test]
+    fn test_strict_parsing() {
+        assert_eq!(parse_number("12.5", ParseMode::Strict).unwrap().value, 12.5);
+        assert!(parse_number(" 12.5", ParseMode::Strict).is_err());
+        assert!(parse_number("1,234.5", ParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_lenient_parsing() {
+        assert_eq!(lenient("12.5"), (12.5, vec![]));
+        assert_eq!(lenient(" 12.5 "), (12.5, vec![Trimmed]));
+        assert_eq!(lenient("1,234.5"), (1234.5, vec![ThousandsSeparators]));
+        assert_eq!(
+            lenient("1.234,5"),
+            (1234.5, vec![ThousandsSeparators, CommaDecimal])
+        );
+        assert_eq!(lenient("12,5"), (12.5, vec![CommaDecimal]));
+        assert_eq!(lenient("1,234"), (1234.0, vec![ThousandsSeparators]));
+        assert_eq!(
+            lenient("1.234.567"),
+            (1_234_567.0, vec![ThousandsSeparators])
+        );
+        assert_eq!(lenient("1.5e3"), (1500.0, vec![ScientificNotation]));
+        assert_eq!(
+            lenient("2,5E-1"),
+            (0.25, vec![ScientificNotation, CommaDecimal])
+        );
+        assert!(parse_number("twelve", ParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_lenient_point_is_decimal() {
+        assert_eq!(lenient("0.250"), (0.25, vec![]));
+        assert_eq!(lenient("12.500"), (12.5, vec![]));
+        assert_eq!(lenient("1.234"), (1.234, vec![]));
+        assert_eq!(lenient("0,250"), (0.25, vec![CommaDecimal]));
+        assert_eq!(lenient("-0,5"), (-0.5, vec![CommaDecimal]));
+        assert_eq!(
+            lenient("1,234,567"),
+            (1_234_567.0, vec![ThousandsSeparators])
+        );
+        for text in [
+            "12,34,5",
+            "1.23.4",
+            "1,2345.6",
+            "1.234.5,6,7",
+            "1,23.4",
+            "0.250.000",
+            "0,250.5",
+        ] {
+            assert!(
+                parse_number(text, ParseMode::Lenient).is_err(),
+                "{} should be rejected",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_number_format() {
+        let comma = NumberFormat::COMMA;
+        assert_eq!(comma.parse("1.234,5"), Ok(1234.5));
+        assert_eq!(comma.parse("1,234"), Ok(1.234));
+        assert_eq!(comma.parse("-12"), Ok(-12.0));
+        assert!(comma.parse("1.5").is_err());
+        assert!(comma.parse("1,234.5").is_err());
+        assert_eq!(
+            parse_number(" 1.234,5", ParseMode::Format(comma))
+                .unwrap()
+                .normalizations,
+            vec![Trimmed, ThousandsSeparators, CommaDecimal]
+        );
+
+        assert_eq!(comma.format(1_234_567.891, Some(2)), "1.234.567,89");
+        assert_eq!(comma.format(-1_000.0, None), "-1.000");
+        assert_eq!(NumberFormat::default().format(1234.5, None), "1234.5");
+
+        let french: NumberFormat = "1 234,5".parse().unwrap();
+        assert_eq!(french, NumberFormat::new(',', Some(' ')));
+        assert_eq!(french.to_string(), "1 234,5");
+        assert_eq!("1234.5".parse(), Ok(NumberFormat::default()));
+        assert!("1,234,5".parse::<NumberFormat>().is_err());
+        assert!("12345".parse::<NumberFormat>().is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! First-fit-decreasing 3D bin packing for cartonization.
+//!
+//! Coordinates are measured from a carton's corner: `x` along its width, `y`
+//! along its height (up) and `z` along its length.
+
+use crate::utilization::Container;
+use crate::{Centimeters, Kilograms, Package};
+
+/// Where a package was placed inside a carton, and in which orientation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// Index of the package in the input slice
+    pub item: usize,
+    pub x: Centimeters,
+    pub y: Centimeters,
+    pub z: Centimeters,
+    /// Size of the package along `x` as placed
+    pub width: Centimeters,
+    /// Size of the package along `y` as placed
+    pub height: Centimeters,
+    /// Size of the package along `z` as placed
+    pub length: Centimeters,
+}
+
+/// A carton opened during packing, with the packages placed in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackedCarton {
+    /// Index of the carton size in the input slice
+    pub carton: usize,
+    pub placements: Vec<Placement>,
+    /// Combined mass of the packed packages
+    pub mass: Kilograms,
+}
+
+/// The result of packing a set of packages into cartons
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackingResult {
+    pub cartons: Vec<PackedCarton>,
+    /// Indices of packages that don't fit in any carton size
+    pub leftover: Vec<usize>,
+}
+
+/// An empty cuboid inside a carton that packages can still be placed in
+#[derive(Debug, Clone, Copy)]
+struct Space {
+    x: f64,
+    y: f64,
+    z: f64,
+    width: f64,
+    height: f64,
+    length: f64,
+}
+
+/// Returns the six axis-aligned orientations of a package as
+/// `[width, height, length]`
+pub(crate) fn orientations(package: &Package) -> [[f64; 3]; 6] {
+    let (w, h, l) = (
+        package.width.value(),
+        package.height.value(),
+        package.length.value(),
+    );
+    [
+        [w, h, l],
+        [w, l, h],
+        [h, w, l],
+        [h, l, w],
+        [l, w, h],
+        [l, h, w],
+    ]
+}
+
+/// Checks if a package fits into an empty container in some orientation
+pub(crate) fn fits_in(package: &Package, container: &Container) -> bool {
+    package.mass <= container.max_mass
+        && orientations(package).iter().any(|&[w, h, l]| {
+            w <= container.width.value()
+                && h <= container.height.value()
+                && l <= container.length.value()
+        })
+}
+
+struct OpenCarton {
+    packed: PackedCarton,
+    max_mass: f64,
+    spaces: Vec<Space>,
+}
+
+impl OpenCarton {
+    fn new(index: usize, carton: &Container) -> Self {
+        Self {
+            packed: PackedCarton {
+                carton: index,
+                placements: Vec::new(),
+                mass: Kilograms::new(0.0),
+            },
+            max_mass: carton.max_mass.value(),
+            spaces: vec![Space {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                width: carton.width.value(),
+                height: carton.height.value(),
+                length: carton.length.value(),
+            }],
+        }
+    }
+
+    /// Places the package in the lowest, then front-most, free space it fits
+    /// in, splitting the remaining space guillotine-style
+    fn try_place(&mut self, item: usize, package: &Package) -> bool {
+        let mass = self.packed.mass.value() + package.mass.value();
+        if mass > self.max_mass {
+            return false;
+        }
+
+        self.spaces.sort_by(|a, b| {
+            a.y.total_cmp(&b.y)
+                .then(a.z.total_cmp(&b.z))
+                .then(a.x.total_cmp(&b.x))
+        });
+
+        for (i, space) in self.spaces.iter().enumerate() {
+            let Some([w, h, l]) = orientations(package)
+                .into_iter()
+                .find(|&[w, h, l]| w <= space.width && h <= space.height && l <= space.length)
+            else {
+                continue;
+            };
+
+            let space = self.spaces.swap_remove(i);
+            let remainders = [
+                // Beside the package, full height and length
+                Space {
+                    x: space.x + w,
+                    width: space.width - w,
+                    ..space
+                },
+                // In front of the package
+                Space {
+                    z: space.z + l,
+                    width: w,
+                    length: space.length - l,
+                    ..space
+                },
+                // On top of the package
+                Space {
+                    y: space.y + h,
+                    width: w,
+                    height: space.height - h,
+                    length: l,
+                    ..space
+                },
+            ];
+            self.spaces.extend(
+                remainders
+                    .into_iter()
+                    .filter(|s| s.width > 0.0 && s.height > 0.0 && s.length > 0.0),
+            );
+
+            self.packed.placements.push(Placement {
+                item,
+                x: Centimeters::new(space.x),
+                y: Centimeters::new(space.y),
+                z: Centimeters::new(space.z),
+                width: Centimeters::new(w),
+                height: Centimeters::new(h),
+                length: Centimeters::new(l),
+            });
+            self.packed.mass = Kilograms::new(mass);
+            return true;
+        }
+
+        false
+    }
+}
+
+/// Packs packages into cartons using a first-fit-decreasing heuristic.
+///
+/// Packages are taken largest volume first and placed into the first open
+/// carton with room for them. When none has room, a new carton of the
+/// smallest size that can hold the package is opened. Any number of cartons
+/// of each size may be used.
+pub fn pack(items: &[Package], cartons: &[Container]) -> PackingResult {
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| items[b].volume().total_cmp(&items[a].volume()));
+
+    let mut open: Vec<OpenCarton> = Vec::new();
+    let mut leftover = Vec::new();
+
+    for item in order {
+        let package = &items[item];
+        if open
+            .iter_mut()
+            .any(|carton| carton.try_place(item, package))
+        {
+            continue;
+        }
+
+        let smallest = cartons
+            .iter()
+            .enumerate()
+            .filter(|(_, carton)| fits_in(package, carton))
+            .min_by(|(_, a), (_, b)| a.volume().total_cmp(&b.volume()));
+
+        match smallest {
+            Some((index, carton)) => {
+                let mut carton = OpenCarton::new(index, carton);
+                carton.try_place(item, package);
+                open.push(carton);
+            }
+            None => leftover.push(item),
+        }
+    }
+
+    leftover.sort_unstable();
+    PackingResult {
+        cartons: open.into_iter().map(|carton| carton.packed).collect(),
+        leftover,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube(side: f64, mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(side),
+            Centimeters::new(side),
+            Centimeters::new(side),
+            Kilograms::new(mass),
+        )
+    }
+
+    fn carton(width: f64, height: f64, length: f64, max_mass: f64) -> Container {
+        Container::new(
+            Centimeters::new(width),
+            Centimeters::new(height),
+            Centimeters::new(length),
+            Kilograms::new(max_mass),
+        )
+    }
+
+    #[test]
+    fn test_pack_into_one_carton() {
+        let items = vec![cube(10.0, 1.0); 8];
+        let result = pack(&items, &[carton(20.0, 20.0, 20.0, 30.0)]);
+
+        assert_eq!(result.cartons.len(), 1);
+        assert_eq!(result.cartons[0].placements.len(), 8);
+        assert_eq!(result.cartons[0].mass, Kilograms::new(8.0));
+        assert!(result.leftover.is_empty());
+    }
+
+    #[test]
+    fn test_pack_picks_smallest_carton_and_reports_leftovers() {
+        let items = vec![
+            cube(10.0, 1.0),
+            cube(30.0, 2.0),
+            // Too big for any carton
+            cube(60.0, 1.0),
+        ];
+        let cartons = [
+            carton(40.0, 40.0, 40.0, 30.0),
+            carton(12.0, 12.0, 12.0, 30.0),
+        ];
+        let result = pack(&items, &cartons);
+
+        // The 30 cm cube opens the large carton and the 10 cm cube joins it
+        assert_eq!(result.cartons.len(), 1);
+        assert_eq!(result.cartons[0].carton, 0);
+        assert_eq!(result.leftover, vec![2]);
+    }
+
+    #[test]
+    fn test_pack_respects_mass_and_rotation() {
+        // A long item only fits once rotated
+        let long = Package::new(
+            Centimeters::new(50.0),
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Kilograms::new(6.0),
+        );
+        let items = vec![long, long];
+        let result = pack(&items, &[carton(10.0, 20.0, 50.0, 10.0)]);
+
+        // Both fit by volume, but not by mass
+        assert_eq!(result.cartons.len(), 2);
+        let placement = result.cartons[0].placements[0];
+        assert_eq!(placement.length, Centimeters::new(50.0));
+    }
+}
@@ -0,0 +1,326 @@
+//! Pallet planning: stacks packages onto pallets layer by layer.
+//!
+//! Positions use the same axes as [`packing`](crate::packing): `x` along the
+//! pallet width, `y` up from the pallet deck and `z` along its length.
+
+use crate::packing::Placement;
+use crate::{Centimeters, Kilograms, Package, SortCategory};
+
+/// Size and load limits of a pallet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PalletSpec {
+    pub width: Centimeters,
+    pub length: Centimeters,
+    /// Maximum height of the load above the deck
+    pub max_height: Centimeters,
+    /// Maximum mass of the load
+    pub max_mass: Kilograms,
+}
+
+impl PalletSpec {
+    /// Creates a new pallet spec with the given deck size and load limits
+    pub fn new(
+        width: Centimeters,
+        length: Centimeters,
+        max_height: Centimeters,
+        max_mass: Kilograms,
+    ) -> Self {
+        Self {
+            width,
+            length,
+            max_height,
+            max_mass,
+        }
+    }
+}
+
+/// A single layer of packages on a pallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    /// Height of the bottom of the layer above the deck
+    pub y: Centimeters,
+    /// Height of the tallest package in the layer
+    pub height: Centimeters,
+    pub placements: Vec<Placement>,
+}
+
+/// A planned pallet holding packages of a single category
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pallet {
+    pub category: SortCategory,
+    pub layers: Vec<Layer>,
+    /// Combined mass of the load
+    pub mass: Kilograms,
+}
+
+impl Pallet {
+    /// Height of the load above the deck
+    pub fn height(&self) -> Centimeters {
+        self.layers.last().map_or(Centimeters::new(0.0), |layer| {
+            Centimeters::new(layer.y.value() + layer.height.value())
+        })
+    }
+
+    /// Iterates over every placement on the pallet, bottom layer first
+    pub fn placements(&self) -> impl Iterator<Item = &Placement> {
+        self.layers.iter().flat_map(|layer| layer.placements.iter())
+    }
+}
+
+/// The result of planning pallets for a batch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PalletPlan {
+    pub pallets: Vec<Pallet>,
+    /// Indices of packages that can't go on a pallet: rejected packages, and
+    /// packages too large or heavy for an empty pallet
+    pub unpalletized: Vec<usize>,
+}
+
+impl PalletPlan {
+    /// Counts the pallets planned for a category
+    pub fn pallet_count(&self, category: SortCategory) -> usize {
+        self.pallets
+            .iter()
+            .filter(|pallet| pallet.category == category)
+            .count()
+    }
+}
+
+/// Fills one pallet row by row, layer by layer
+struct PalletBuilder<'a> {
+    spec: &'a PalletSpec,
+    pallet: Pallet,
+    layer: Layer,
+    row_x: f64,
+    row_z: f64,
+    row_depth: f64,
+}
+
+impl<'a> PalletBuilder<'a> {
+    fn new(spec: &'a PalletSpec, category: SortCategory) -> Self {
+        Self {
+            spec,
+            pallet: Pallet {
+                category,
+                layers: Vec::new(),
+                mass: Kilograms::new(0.0),
+            },
+            layer: Layer {
+                y: Centimeters::new(0.0),
+                height: Centimeters::new(0.0),
+                placements: Vec::new(),
+            },
+            row_x: 0.0,
+            row_z: 0.0,
+            row_depth: 0.0,
+        }
+    }
+
+    /// Places a package lying flat (smallest dimension up): in the current
+    /// row, else a new row, else a new layer
+    fn try_place(&mut self, item: usize, package: &Package) -> bool {
+        let mass = self.pallet.mass.value() + package.mass.value();
+        let flat = package.normalized();
+        let (long, wide, high) = (flat.length.value(), flat.width.value(), flat.height.value());
+        if mass > self.spec.max_mass.value()
+            || self.layer.y.value() + high > self.spec.max_height.value()
+        {
+            return false;
+        }
+
+        for attempt in 0..3 {
+            match attempt {
+                1 => {
+                    self.row_x = 0.0;
+                    self.row_z += self.row_depth;
+                    self.row_depth = 0.0;
+                }
+                2 => {
+                    let y = self.layer.y.value() + self.layer.height.value();
+                    if self.layer.placements.is_empty() || y + high > self.spec.max_height.value() {
+                        return false;
+                    }
+                    let full = std::mem::replace(
+                        &mut self.layer,
+                        Layer {
+                            y: Centimeters::new(y),
+                            height: Centimeters::new(0.0),
+                            placements: Vec::new(),
+                        },
+                    );
+                    self.pallet.layers.push(full);
+                    self.row_x = 0.0;
+                    self.row_z = 0.0;
+                    self.row_depth = 0.0;
+                }
+                _ => {}
+            }
+
+            let footprint = [(long, wide), (wide, long)].into_iter().find(|&(w, l)| {
+                self.row_x + w <= self.spec.width.value()
+                    && self.row_z + l <= self.spec.length.value()
+            });
+            if let Some((w, l)) = footprint {
+                self.layer.placements.push(Placement {
+                    item,
+                    x: Centimeters::new(self.row_x),
+                    y: self.layer.y,
+                    z: Centimeters::new(self.row_z),
+                    width: Centimeters::new(w),
+                    height: Centimeters::new(high),
+                    length: Centimeters::new(l),
+                });
+                self.layer.height = Centimeters::new(self.layer.height.value().max(high));
+                self.row_x += w;
+                self.row_depth = self.row_depth.max(l);
+                self.pallet.mass = Kilograms::new(mass);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn finish(mut self) -> Pallet {
+        if !self.layer.placements.is_empty() {
+            self.pallet.layers.push(self.layer);
+        }
+        self.pallet
+    }
+}
+
+/// Checks if a package fits on an empty pallet at all
+fn fits_on(package: &Package, spec: &PalletSpec) -> bool {
+    let flat = package.normalized();
+    let (long, wide) = (flat.length, flat.width);
+
+    package.mass <= spec.max_mass
+        && flat.height <= spec.max_height
+        && ((long <= spec.width && wide <= spec.length)
+            || (wide <= spec.width && long <= spec.length))
+}
+
+/// Plans pallets for a batch of packages.
+///
+/// Each category gets its own pallets, since they leave on different lanes;
+/// rejected packages are never palletized. Packages lie flat and are placed
+/// tallest first, in rows across the deck and layers up to the height limit,
+/// with fragile packages held back for the upper layers.
+pub fn plan_pallets(packages: &[Package], spec: &PalletSpec) -> PalletPlan {
+    let mut plan = PalletPlan::default();
+    let categories = [
+        SortCategory::Standard,
+        SortCategory::Special,
+        SortCategory::Perishable,
+    ];
+
+    for category in categories {
+        let mut items: Vec<usize> = (0..packages.len())
+            .filter(|&i| packages[i].sort_category() == category)
+            .collect();
+        items.sort_by(|&a, &b| {
+            let (a, b) = (packages[a].normalized(), packages[b].normalized());
+            a.fragile
+                .cmp(&b.fragile)
+                .then(b.height.value().total_cmp(&a.height.value()))
+        });
+
+        let mut builder: Option<PalletBuilder> = None;
+        for item in items {
+            let package = &packages[item];
+            if !fits_on(package, spec) {
+                plan.unpalletized.push(item);
+                continue;
+            }
+
+            let placed = builder
+                .as_mut()
+                .is_some_and(|builder| builder.try_place(item, package));
+            if !placed {
+                if let Some(full) = builder.take() {
+                    plan.pallets.push(full.finish());
+                }
+                let mut fresh = PalletBuilder::new(spec, category);
+                fresh.try_place(item, package);
+                builder = Some(fresh);
+            }
+        }
+
+        if let Some(builder) = builder {
+            plan.pallets.push(builder.finish());
+        }
+    }
+
+    plan.unpalletized.extend(
+        (0..packages.len()).filter(|&i| packages[i].sort_category() == SortCategory::Rejected),
+    );
+    plan.unpalletized.sort_unstable();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> PalletSpec {
+        // Euro pallet, 150 cm load height, 500 kg
+        PalletSpec::new(
+            Centimeters::new(80.0),
+            Centimeters::new(120.0),
+            Centimeters::new(150.0),
+            Kilograms::new(500.0),
+        )
+    }
+
+    fn package(width: f64, height: f64, length: f64, mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(width),
+            Centimeters::new(height),
+            Centimeters::new(length),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_layers_fill_deck_before_stacking() {
+        // Six 40 x 40 x 30 boxes cover the 80 x 120 deck in one layer
+        let packages = vec![package(40.0, 30.0, 40.0, 5.0); 7];
+        let plan = plan_pallets(&packages, &spec());
+
+        assert_eq!(plan.pallets.len(), 1);
+        let pallet = &plan.pallets[0];
+        assert_eq!(pallet.layers.len(), 2);
+        assert_eq!(pallet.layers[0].placements.len(), 6);
+        assert_eq!(pallet.layers[1].y, Centimeters::new(30.0));
+        assert_eq!(pallet.height(), Centimeters::new(60.0));
+        assert_eq!(pallet.mass, Kilograms::new(35.0));
+    }
+
+    #[test]
+    fn test_pallet_counts_per_category() {
+        let mut packages = vec![package(40.0, 30.0, 40.0, 15.0); 40];
+        // Heavy packages go on their own pallets
+        packages.extend(vec![package(40.0, 30.0, 40.0, 25.0); 3]);
+        // Rejected and oversized packages aren't palletized
+        packages.push(package(160.0, 50.0, 50.0, 25.0));
+        packages.push(package(100.0, 100.0, 90.0, 10.0));
+        let plan = plan_pallets(&packages, &spec());
+
+        // 40 x 15 kg exceeds the 500 kg limit: two standard pallets
+        assert_eq!(plan.pallet_count(SortCategory::Standard), 2);
+        assert_eq!(plan.pallet_count(SortCategory::Special), 1);
+        assert_eq!(plan.unpalletized, vec![43, 44]);
+    }
+
+    #[test]
+    fn test_fragile_packages_go_on_top() {
+        let mut packages = vec![package(40.0, 40.0, 40.0, 5.0).with_fragile()];
+        packages.extend(vec![package(40.0, 30.0, 40.0, 5.0); 6]);
+        let plan = plan_pallets(&packages, &spec());
+
+        let pallet = &plan.pallets[0];
+        assert_eq!(pallet.layers.len(), 2);
+        assert_eq!(pallet.layers[1].placements[0].item, 0);
+        assert_eq!(pallet.placements().count(), 7);
+    }
+}
@@ -0,0 +1,236 @@
+//! Composable package predicates for custom rule trees.
+//!
+//! The primitives are the building blocks of the built-in bulky and heavy
+//! rules; combined with [`Predicate::and`], [`Predicate::or`] and
+//! [`Predicate::not`] and mapped to categories by a [`Policy`], they express
+//! site policies such as "bulky and fragile packages are rejected".
+
+use std::fmt;
+
+use crate::{Centimeters, Kilograms, Package, SortCategory};
+
+/// A condition on a package
+pub trait Predicate: fmt::Debug {
+    /// Checks if the package meets the condition
+    fn test(&self, package: &Package) -> bool;
+
+    /// Met when both `self` and `other` are
+    fn and<P: Predicate>(self, other: P) -> And<Self, P>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    /// Met when `self`, `other` or both are
+    fn or<P: Predicate>(self, other: P) -> Or<Self, P>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    /// Met when `self` isn't
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+}
+
+impl<P: Predicate + ?Sized> Predicate for Box<P> {
+    fn test(&self, package: &Package) -> bool {
+        (**self).test(package)
+    }
+}
+
+/// Volume at or above the given number of cubic centimeters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeAtLeast(pub f64);
+
+impl Predicate for VolumeAtLeast {
+    fn test(&self, package: &Package) -> bool {
+        package.volume() >= self.0
+    }
+}
+
+/// Any dimension at or above the given length
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnyDimensionAtLeast(pub Centimeters);
+
+impl Predicate for AnyDimensionAtLeast {
+    fn test(&self, package: &Package) -> bool {
+        [package.width, package.height, package.length]
+            .iter()
+            .any(|&dimension| dimension >= self.0)
+    }
+}
+
+/// Mass at or above the given mass
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassAtLeast(pub Kilograms);
+
+impl Predicate for MassAtLeast {
+    fn test(&self, package: &Package) -> bool {
+        package.mass >= self.0
+    }
+}
+
+/// The package is marked fragile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fragile;
+
+impl Predicate for Fragile {
+    fn test(&self, package: &Package) -> bool {
+        package.fragile
+    }
+}
+
+/// The package is marked as hazardous materials
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hazmat;
+
+impl Predicate for Hazmat {
+    fn test(&self, package: &Package) -> bool {
+        package.hazmat
+    }
+}
+
+/// The package has a required temperature range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Perishable;
+
+impl Predicate for Perishable {
+    fn test(&self, package: &Package) -> bool {
+        package.is_perishable()
+    }
+}
+
+/// Met when both predicates are
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct And<A, B>(pub A, pub B);
+
+impl<A: Predicate, B: Predicate> Predicate for And<A, B> {
+    fn test(&self, package: &Package) -> bool {
+        self.0.test(package) && self.1.test(package)
+    }
+}
+
+/// Met when either predicate is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Or<A, B>(pub A, pub B);
+
+impl<A: Predicate, B: Predicate> Predicate for Or<A, B> {
+    fn test(&self, package: &Package) -> bool {
+        self.0.test(package) || self.1.test(package)
+    }
+}
+
+/// Met when the predicate isn't
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Not<A>(pub A);
+
+impl<A: Predicate> Predicate for Not<A> {
+    fn test(&self, package: &Package) -> bool {
+        !self.0.test(package)
+    }
+}
+
+/// An ordered list of predicates mapped to categories; the first one a
+/// package meets decides its category
+#[derive(Debug)]
+pub struct Policy {
+    rules: Vec<(Box<dyn Predicate>, SortCategory)>,
+    default: SortCategory,
+}
+
+impl Policy {
+    /// Creates a policy sorting every package into `default`
+    pub fn new(default: SortCategory) -> Self {
+        Self {
+            rules: Vec::new(),
+            default,
+        }
+    }
+
+    /// Adds a rule after the existing ones: packages meeting `predicate` go
+    /// to `category`
+    pub fn when(mut self, predicate: impl Predicate + 'static, category: SortCategory) -> Self {
+        self.rules.push((Box::new(predicate), category));
+        self
+    }
+
+    /// Returns the category of the first rule the package meets, or the
+    /// default
+    pub fn category(&self, package: &Package) -> SortCategory {
+        self.rules
+            .iter()
+            .find(|(predicate, _)| predicate.test(package))
+            .map_or(self.default, |&(_, category)| category)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Celsius, TemperatureRange};
+
+    fn package(w: f64, h: f64, l: f64, m: f64) -> Package {
+        Package::new(
+            Centimeters::new(w),
+            Centimeters::new(h),
+            Centimeters::new(l),
+            Kilograms::new(m),
+        )
+    }
+
+    #[test]
+    fn test_primitives_match_default_rules() {
+        let bulky = VolumeAtLeast(1_000_000.0).or(AnyDimensionAtLeast(Centimeters::new(150.0)));
+        let heavy = MassAtLeast(Kilograms::new(20.0));
+        for pkg in [
+            package(50.0, 50.0, 50.0, 10.0),
+            package(100.0, 100.0, 100.0, 10.0),
+            package(150.0, 10.0, 10.0, 10.0),
+            package(149.9, 10.0, 10.0, 20.0),
+            package(10.0, 10.0, 10.0, 19.99),
+        ] {
+            assert_eq!(bulky.test(&pkg), pkg.is_bulky());
+            assert_eq!(heavy.test(&pkg), pkg.is_heavy());
+        }
+    }
+
+    #[test]
+    fn test_policy() {
+        let bulky = VolumeAtLeast(1_000_000.0).or(AnyDimensionAtLeast(Centimeters::new(150.0)));
+        let policy = Policy::new(SortCategory::Standard)
+            .when(bulky.and(Fragile), SortCategory::Rejected)
+            .when(
+                bulky.or(MassAtLeast(Kilograms::new(20.0))),
+                SortCategory::Special,
+            )
+            .when(Perishable.and(Hazmat.not()), SortCategory::Perishable);
+
+        let long = package(160.0, 50.0, 50.0, 10.0);
+        assert_eq!(policy.category(&long), SortCategory::Special);
+        assert_eq!(
+            policy.category(&long.with_fragile()),
+            SortCategory::Rejected
+        );
+        let small = package(20.0, 20.0, 20.0, 1.0);
+        assert_eq!(policy.category(&small), SortCategory::Standard);
+        assert_eq!(
+            policy.category(&small.with_fragile()),
+            SortCategory::Standard
+        );
+
+        let chilled = small
+            .with_temperature_range(TemperatureRange::new(Celsius::new(2.0), Celsius::new(8.0)));
+        assert_eq!(policy.category(&chilled), SortCategory::Perishable);
+        assert_eq!(
+            policy.category(&chilled.with_hazmat()),
+            SortCategory::Standard
+        );
+    }
+}
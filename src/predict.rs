@@ -0,0 +1,125 @@
+//! Hooks for model-based predictions (e.g. likelihood of damage or of a
+//! mismeasurement).
+//!
+//! Predictions are advisory: the rules always decide the category, but a
+//! prediction can flag the decision for review.
+
+use crate::{Package, SortCategory, SortDecision, SortRules};
+
+/// A single model's opinion about a package
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prediction {
+    /// Name of the model that made the prediction
+    pub model: String,
+    /// Likelihood of whatever the model detects, from 0 to 1
+    pub score: f64,
+    /// The category the model would have chosen, if it predicts categories
+    pub category: Option<SortCategory>,
+}
+
+/// A model the sorter can consult about each package
+pub trait Predictor {
+    /// Makes a prediction for a package the rules have already sorted
+    fn predict(&self, package: &Package, decision: &SortDecision) -> Prediction;
+
+    /// Score at or above which the decision is flagged for review
+    fn review_threshold(&self) -> f64 {
+        0.5
+    }
+}
+
+/// Sorts a package under the rules, then attaches every predictor's
+/// prediction to the decision.
+///
+/// The decision is flagged for review if any prediction scores at or above
+/// its predictor's threshold, or predicts a different category than the
+/// rules chose.
+pub fn decide_with_predictors(
+    rules: &SortRules,
+    package: &Package,
+    predictors: &[&dyn Predictor],
+) -> SortDecision {
+    let mut decision = rules.decide(package);
+
+    for predictor in predictors {
+        let prediction = predictor.predict(package, &decision);
+        let disagrees = prediction
+            .category
+            .is_some_and(|category| category != decision.category);
+        if prediction.score >= predictor.review_threshold() || disagrees {
+            decision.needs_review = true;
+        }
+        decision.predictions.push(prediction);
+    }
+
+    decision
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Centimeters, Kilograms};
+
+    /// Flags light packages in large boxes as likely mismeasured
+    struct LowDensity;
+
+    impl Predictor for LowDensity {
+        fn predict(&self, package: &Package, _decision: &SortDecision) -> Prediction {
+            Prediction {
+                model: "low-density".to_string(),
+                score: if package.density() < 10.0 { 0.9 } else { 0.1 },
+                category: None,
+            }
+        }
+    }
+
+    /// Always predicts the same category
+    struct Fixed(SortCategory);
+
+    impl Predictor for Fixed {
+        fn predict(&self, _package: &Package, _decision: &SortDecision) -> Prediction {
+            Prediction {
+                model: "fixed".to_string(),
+                score: 0.0,
+                category: Some(self.0),
+            }
+        }
+    }
+
+    fn package(mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_predictions_are_attached() {
+        let rules = SortRules::default();
+        let decision = decide_with_predictors(&rules, &package(10.0), &[&LowDensity]);
+
+        assert_eq!(decision.category, SortCategory::Standard);
+        assert_eq!(decision.predictions.len(), 1);
+        assert_eq!(decision.predictions[0].model, "low-density");
+        assert!(!decision.needs_review);
+    }
+
+    #[test]
+    fn test_predictions_flag_review() {
+        let rules = SortRules::default();
+
+        // High score
+        let decision = decide_with_predictors(&rules, &package(0.5), &[&LowDensity]);
+        assert_eq!(decision.category, SortCategory::Standard);
+        assert!(decision.needs_review);
+
+        // Disagreement on the category; the rules still win
+        let predictors: [&dyn Predictor; 2] = [&LowDensity, &Fixed(SortCategory::Special)];
+        let decision = decide_with_predictors(&rules, &package(10.0), &predictors);
+        assert_eq!(decision.category, SortCategory::Standard);
+        assert_eq!(decision.predictions.len(), 2);
+        assert!(decision.needs_review);
+    }
+}
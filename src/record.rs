@@ -0,0 +1,293 @@
+//! The machine-readable decision schema.
+//!
+//! Every machine-readable output emits one [`DecisionRecord`] per package as
+//! a single-line JSON object. Each record carries `schema_version`.
+//! Compatibility rules:
+//!
+//! - Within a schema version, fields are never removed, renamed or changed in
+//!   type or meaning.
+//! - New fields may be added without a version bump; consumers must ignore
+//!   fields they don't know.
+//! - New values may appear in `category`, `reasons`, `oversize_axes` and
+//!   `suspect`; consumers should treat unknown values as needing manual
+//!   handling.
+//! - Anything else bumps [`SCHEMA_VERSION`].
+//!
+//! Version 1 fields, in order:
+//!
+//! | Field                   | Type                 |
+//! | ----------------------- | -------------------- |
+//! | `schema_version`        | number               |
+//! | `package_id`            | string or null       |
+//! | `category`              | string               |
+//! | `reasons`               | array of strings     |
+//! | `oversize_axes`         | array of strings     |
+//! | `width_cm`              | number               |
+//! | `height_cm`             | number               |
+//! | `length_cm`             | number               |
+//! | `mass_kg`               | number               |
+//! | `volume_cm3`            | number               |
+//! | `dimensional_weight_kg` | number               |
+//! | `chargeable_weight_kg`  | number               |
+//! | `stackability`          | number (0–100)       |
+//! | `time_sensitive`        | boolean              |
+//! | `suspect`               | string or null       |
+//! | `needs_review`          | boolean              |
+//! | `rule_version`          | string               |
+//! | `notes`                 | array of strings     |
+//!
+//! Non-finite numbers are written as `null`. `category` holds the configured
+//! output label of the category (see `labels`), which is the category name
+//! unless remapped.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::json;
+use crate::labels::CategoryLabels;
+use crate::{Package, SortDecision};
+
+/// Version of the decision schema emitted by this build
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// How the CLI writes decisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON [`DecisionRecord`] per line
+    Json,
+}
+
+impl OutputFormat {
+    /// Returns the setting name of the format
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = ParseOutputFormatError;
+
+    /// Parses `text` or `json` (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [OutputFormat::Text, OutputFormat::Json]
+            .into_iter()
+            .find(|format| format.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ParseOutputFormatError(s.to_string()))
+    }
+}
+
+/// Error returned when a string isn't a known output format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOutputFormatError(String);
+
+impl fmt::Display for ParseOutputFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown output format {:?}: expected text or json",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseOutputFormatError {}
+
+/// A decision in the versioned, machine-readable schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionRecord<'a> {
+    pub package_id: Option<&'a str>,
+    pub package: &'a Package,
+    pub decision: &'a SortDecision,
+    /// Output labels written in `category`; `SortCategory::as_str` if unset
+    pub labels: Option<&'a CategoryLabels>,
+    /// Free-text handling notes, e.g. "label damaged"
+    pub notes: &'a [String],
+}
+
+impl<'a> DecisionRecord<'a> {
+    pub fn new(
+        package_id: Option<&'a str>,
+        package: &'a Package,
+        decision: &'a SortDecision,
+    ) -> Self {
+        Self {
+            package_id,
+            package,
+            decision,
+            labels: None,
+            notes: &[],
+        }
+    }
+
+    /// Writes `category` with the given output labels
+    pub fn with_labels(mut self, labels: &'a CategoryLabels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Writes the given handling notes in `notes`
+    pub fn with_notes(mut self, notes: &'a [String]) -> Self {
+        self.notes = notes;
+        self
+    }
+
+    /// Writes the record as a single-line JSON object
+    pub fn to_json(&self) -> String {
+        json::object(&self.fields())
+    }
+
+    /// Returns the record's fields in schema order, as JSON values
+    pub(crate) fn fields(&self) -> Vec<(&'static str, String)> {
+        let (package, decision) = (self.package, self.decision);
+        let reasons: Vec<&str> = decision.reasons().iter().map(|r| r.as_str()).collect();
+        let axes: Vec<&str> = decision.oversize_axes.iter().map(|a| a.as_str()).collect();
+
+        let mut fields = Vec::new();
+        let mut field = |name: &'static str, value: String| fields.push((name, value));
+        field("schema_version", SCHEMA_VERSION.to_string());
+        field(
+            "package_id",
+            self.package_id
+                .map_or_else(|| "null".to_string(), json::string),
+        );
+        let category = self.labels.map_or(decision.category.as_str(), |labels| {
+            labels.label(decision.category)
+        });
+        field("category", json::string(category));
+        field("reasons", json::strings(&reasons));
+        field("oversize_axes", json::strings(&axes));
+        field("width_cm", json::number(package.width.value()));
+        field("height_cm", json::number(package.height.value()));
+        field("length_cm", json::number(package.length.value()));
+        field("mass_kg", json::number(package.mass.value()));
+        field("volume_cm3", json::number(package.volume()));
+        field(
+            "dimensional_weight_kg",
+            json::number(decision.dimensional_weight.value()),
+        );
+        field(
+            "chargeable_weight_kg",
+            json::number(decision.chargeable_weight.value()),
+        );
+        field("stackability", decision.stackability.to_string());
+        field("time_sensitive", decision.time_sensitive.to_string());
+        field(
+            "suspect",
+            decision
+                .suspect
+                .map_or_else(|| "null".to_string(), |s| json::string(s.as_str())),
+        );
+        field("needs_review", decision.needs_review.to_string());
+        field("rule_version", json::string(&decision.rule_version));
+        field("notes", json::strings(self.notes));
+        fields
+    }
+}
+
+impl fmt::Display for DecisionRecord<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Centimeters, Kilograms};
+
+    #[test]
+    fn test_decision_record_json() {
+        let package = Package::new(
+            Centimeters::new(160.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Kilograms::new(10.5),
+        );
+        let decision = package.decide();
+        let record = DecisionRecord::new(Some("PKG-\"7\""), &package, &decision);
+        assert_eq!(
+            record.to_json(),
+            "{\"schema_version\":1,\"package_id\":\"PKG-\\\"7\\\"\",\
+             \"category\":\"SPECIAL\",\"reasons\":[\"BULKY\"],\"oversize_axes\":[\"LENGTH\"],\
+             \"width_cm\":160,\"height_cm\":50,\"length_cm\":50,\"mass_kg\":10.5,\
+             \"volume_cm3\":400000,\"dimensional_weight_kg\":80,\
+             \"chargeable_weight_kg\":80,\"stackability\":43,\
+             \"time_sensitive\":false,\"suspect\":null,\"needs_review\":false,\
+             \"rule_version\":\"base\",\"notes\":[]}"
+        );
+
+        let package = Package::new(
+            Centimeters::new(f64::NAN),
+            Centimeters::new(1.0),
+            Centimeters::new(1.0),
+            Kilograms::new(1.0),
+        );
+        let decision = package.decide();
+        let json = DecisionRecord::new(None, &package, &decision).to_json();
+        assert!(json.contains("\"package_id\":null"));
+        assert!(json.contains("\"width_cm\":null"));
+        assert!(json.contains("\"suspect\":\"NON_PHYSICAL\""));
+    }
+
+    #[test]
+    fn test_notes_round_trip() {
+        let package = Package::new(
+            Centimeters::new(30.0),
+            Centimeters::new(20.0),
+            Centimeters::new(10.0),
+            Kilograms::new(2.0),
+        );
+        let decision = package.decide();
+        let notes = vec![
+            "label damaged".to_string(),
+            "re-taped, \"fragile\" sticker\nadded".to_string(),
+        ];
+        let json = DecisionRecord::new(Some("PKG-1"), &package, &decision)
+            .with_notes(&notes)
+            .to_json();
+
+        let parsed = json::parse(&json).unwrap();
+        let read: Vec<&str> = parsed
+            .get("notes")
+            .and_then(json::Value::as_array)
+            .unwrap()
+            .iter()
+            .filter_map(json::Value::as_str)
+            .collect();
+        assert_eq!(read, notes);
+    }
+
+    #[test]
+    fn test_record_uses_labels() {
+        let package = Package::new(
+            Centimeters::new(160.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Kilograms::new(25.0),
+        );
+        let decision = package.decide();
+        let labels: CategoryLabels = "REJECTED=HOLD".parse().unwrap();
+        let json = DecisionRecord::new(None, &package, &decision)
+            .with_labels(&labels)
+            .to_json();
+        assert!(json.contains("\"category\":\"HOLD\""));
+    }
+
+    #[test]
+    fn test_parse_output_format() {
+        assert_eq!("JSON".parse(), Ok(OutputFormat::Json));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,497 @@
+//! Queue of decisions awaiting human review: near-limit, suspect and
+//! model-flagged decisions are held until a reviewer approves them or
+//! overrides their category.
+//!
+//! The queue can be saved as JSON lines, one item per line, and loaded back,
+//! so reviews can be worked through across sessions (see the `review` CLI
+//! subcommand). Each line has these fields:
+//!
+//! | Field               | Type                                        |
+//! | ------------------- | ------------------------------------------- |
+//! | `package_id`        | string                                      |
+//! | `category`          | category identifier                         |
+//! | `rule_version`      | string                                      |
+//! | `reasons`           | array of `NEAR_LIMIT`, `SUSPECT`, `FLAGGED` |
+//! | `suspect`           | suspect measurement identifier or null      |
+//! | `status`            | `PENDING`, `APPROVED` or `RECLASSIFIED`     |
+//! | `reclassified_from` | category identifier or null                 |
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::json::{self, Value};
+use crate::uncertainty::UncertainDecision;
+use crate::{SortCategory, SortDecision, SuspectMeasurement};
+
+/// Why a decision was queued for review
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewReason {
+    /// The package is close enough to a limit to belong to another category
+    NearLimit,
+    /// The measurements look physically implausible
+    Suspect,
+    /// Flagged for review by a predictor or rule (`needs_review`)
+    Flagged,
+}
+
+impl ReviewReason {
+    pub const ALL: [ReviewReason; 3] = [
+        ReviewReason::NearLimit,
+        ReviewReason::Suspect,
+        ReviewReason::Flagged,
+    ];
+
+    /// Returns the stable, machine-readable identifier of the reason
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewReason::NearLimit => "NEAR_LIMIT",
+            ReviewReason::Suspect => "SUSPECT",
+            ReviewReason::Flagged => "FLAGGED",
+        }
+    }
+}
+
+impl fmt::Display for ReviewReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A decision waiting for a reviewer. Only what a reviewer acts on is kept,
+/// so a saved queue restores items exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewItem {
+    pub package_id: String,
+    /// The category the package is routed to
+    pub category: SortCategory,
+    /// The rule set the decision was made under
+    pub rule_version: String,
+    /// Set if the measurements look physically implausible
+    pub suspect: Option<SuspectMeasurement>,
+    pub reasons: Vec<ReviewReason>,
+}
+/// What the reviewer decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// The automatic decision stands
+    Approved,
+    /// The reviewer moved the package to another category
+    Reclassified { from: SortCategory },
+}
+
+/// A reviewed item and its resolution
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedItem {
+    pub item: ReviewItem,
+    pub resolution: Resolution,
+}
+
+impl ResolvedItem {
+    /// Writes the reviewed item as a single-line JSON object, as in a saved
+    /// queue
+    pub fn to_json(&self) -> String {
+        self.item.to_line(Some(self.resolution))
+    }
+}
+
+/// Decisions awaiting review, and those already reviewed
+#[derive(Debug, Clone, Default)]
+pub struct ReviewQueue {
+    pending: Vec<ReviewItem>,
+    resolved: Vec<ResolvedItem>,
+}
+
+impl ReviewQueue {
+    /// Creates an empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the decision if it needs review. Returns whether it was queued.
+    pub fn submit(&mut self, package_id: impl Into<String>, decision: SortDecision) -> bool {
+        self.push(package_id.into(), decision, Vec::new())
+    }
+
+    /// Queues the decision if it needs review or is near a limit. Returns
+    /// whether it was queued.
+    pub fn submit_uncertain(
+        &mut self,
+        package_id: impl Into<String>,
+        uncertain: UncertainDecision,
+    ) -> bool {
+        let reasons = if uncertain.needs_remeasure() {
+            vec![ReviewReason::NearLimit]
+        } else {
+            Vec::new()
+        };
+        self.push(package_id.into(), uncertain.decision, reasons)
+    }
+
+    /// Returns the items awaiting review, oldest first
+    pub fn pending(&self) -> &[ReviewItem] {
+        &self.pending
+    }
+
+    /// Returns the reviewed items, in the order they were resolved
+    pub fn resolved(&self) -> &[ResolvedItem] {
+        &self.resolved
+    }
+
+    /// Accepts the automatic decision for the package
+    pub fn approve(&mut self, package_id: &str) -> Result<&ResolvedItem, ReviewError> {
+        let item = self.take(package_id)?;
+        Ok(self.resolve(item, Resolution::Approved))
+    }
+
+    /// Overrides the package's category
+    pub fn reclassify(
+        &mut self,
+        package_id: &str,
+        category: SortCategory,
+    ) -> Result<&ResolvedItem, ReviewError> {
+        let mut item = self.take(package_id)?;
+        let from = item.category;
+        item.category = category;
+        Ok(self.resolve(item, Resolution::Reclassified { from }))
+    }
+
+    /// Writes the queue as JSON lines: pending items oldest first, then
+    /// resolved items in the order they were resolved
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        for item in &self.pending {
+            writeln!(out, "{}", item.to_line(None))?;
+        }
+        for resolved in &self.resolved {
+            writeln!(out, "{}", resolved.item.to_line(Some(resolved.resolution)))?;
+        }
+        Ok(())
+    }
+
+    /// Saves the queue to a file, replacing it
+    pub fn save(&self, path: &Path) -> Result<(), ReviewQueueError> {
+        let io_error = |source| ReviewQueueError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let mut out = io::BufWriter::new(fs::File::create(path).map_err(io_error)?);
+        self.write(&mut out)
+            .and_then(|()| out.flush())
+            .map_err(io_error)
+    }
+
+    /// Reads a queue written by [`ReviewQueue::write`]. Blank lines are
+    /// skipped.
+    pub fn read(text: &str) -> Result<Self, ReviewQueueError> {
+        let mut queue = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (item, resolution) =
+                ReviewItem::from_line(line).map_err(|message| ReviewQueueError::Syntax {
+                    line: index + 1,
+                    message,
+                })?;
+            match resolution {
+                None => queue.pending.push(item),
+                Some(resolution) => queue.resolved.push(ResolvedItem { item, resolution }),
+            }
+        }
+        Ok(queue)
+    }
+
+    /// Loads a queue saved by [`ReviewQueue::save`]
+    pub fn load(path: &Path) -> Result<Self, ReviewQueueError> {
+        let text = fs::read_to_string(path).map_err(|source| ReviewQueueError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::read(&text)
+    }
+
+    fn push(
+        &mut self,
+        package_id: String,
+        decision: SortDecision,
+        mut reasons: Vec<ReviewReason>,
+    ) -> bool {
+        if decision.suspect.is_some() {
+            reasons.push(ReviewReason::Suspect);
+        } else if decision.needs_review {
+            reasons.push(ReviewReason::Flagged);
+        }
+        if reasons.is_empty() {
+            return false;
+        }
+
+        self.pending.push(ReviewItem {
+            package_id,
+            category: decision.category,
+            rule_version: decision.rule_version,
+            suspect: decision.suspect,
+            reasons,
+        });
+        true
+    }
+
+    fn take(&mut self, package_id: &str) -> Result<ReviewItem, ReviewError> {
+        let index = self
+            .pending
+            .iter()
+            .position(|item| item.package_id == package_id)
+            .ok_or_else(|| ReviewError(package_id.to_string()))?;
+        Ok(self.pending.remove(index))
+    }
+
+    fn resolve(&mut self, item: ReviewItem, resolution: Resolution) -> &ResolvedItem {
+        self.resolved.push(ResolvedItem { item, resolution });
+        &self.resolved[self.resolved.len() - 1]
+    }
+}
+
+impl ReviewItem {
+    /// Writes the pending item as a single-line JSON object, as in a saved
+    /// queue
+    pub fn to_json(&self) -> String {
+        self.to_line(None)
+    }
+
+    /// Writes the item as one line of a saved queue, with its resolution if
+    /// it has been reviewed
+    fn to_line(&self, resolution: Option<Resolution>) -> String {
+        let optional = |id: Option<&str>| id.map_or_else(|| "null".to_string(), json::string);
+        let (status, from) = match resolution {
+            None => ("PENDING", None),
+            Some(Resolution::Approved) => ("APPROVED", None),
+            Some(Resolution::Reclassified { from }) => ("RECLASSIFIED", Some(from.as_str())),
+        };
+        let reasons: Vec<&str> = self.reasons.iter().map(ReviewReason::as_str).collect();
+        json::object(&[
+            ("package_id", json::string(&self.package_id)),
+            ("category", json::string(self.category.as_str())),
+            ("rule_version", json::string(&self.rule_version)),
+            ("reasons", json::strings(&reasons)),
+            ("suspect", optional(self.suspect.map(|s| s.as_str()))),
+            ("status", json::string(status)),
+            ("reclassified_from", optional(from)),
+        ])
+    }
+
+    /// Reads one line of a saved queue
+    fn from_line(line: &str) -> Result<(Self, Option<Resolution>), String> {
+        let value = json::parse(line).map_err(|err| err.to_string())?;
+        let field = |name: &str| value.get(name).ok_or(format!("missing {:?}", name));
+        let string = |name: &str| {
+            field(name)?
+                .as_str()
+                .ok_or(format!("{:?} is not a string", name))
+        };
+        let category = |name: &str| {
+            string(name)?
+                .parse::<SortCategory>()
+                .map_err(|err| err.to_string())
+        };
+
+        let reasons = field("reasons")?
+            .as_array()
+            .and_then(|reasons| {
+                reasons
+                    .iter()
+                    .map(|reason| {
+                        let reason = reason.as_str()?;
+                        ReviewReason::ALL.into_iter().find(|r| r.as_str() == reason)
+                    })
+                    .collect()
+            })
+            .ok_or("\"reasons\" is not an array of review reasons")?;
+        let suspect = match field("suspect")? {
+            Value::Null => None,
+            value => {
+                let suspect = value.as_str().unwrap_or_default();
+                let known = [
+                    SuspectMeasurement::NonPhysical,
+                    SuspectMeasurement::TooLight,
+                    SuspectMeasurement::TooDense,
+                ];
+                let found = known.into_iter().find(|s| s.as_str() == suspect);
+                Some(found.ok_or(format!("unknown suspect measurement {:?}", suspect))?)
+            }
+        };
+        let resolution = match string("status")? {
+            "PENDING" => None,
+            "APPROVED" => Some(Resolution::Approved),
+            "RECLASSIFIED" => Some(Resolution::Reclassified {
+                from: category("reclassified_from")?,
+            }),
+            other => return Err(format!("unknown status {:?}", other)),
+        };
+
+        let item = ReviewItem {
+            package_id: string("package_id")?.to_string(),
+            category: category("category")?,
+            rule_version: string("rule_version")?.to_string(),
+            suspect,
+            reasons,
+        };
+        Ok((item, resolution))
+    }
+}
+
+/// Error returned when no pending review matches a package id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewError(String);
+
+impl fmt::Display for ReviewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no pending review for package {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ReviewError {}
+
+/// Error returned when a saved queue can't be written or read
+#[derive(Debug)]
+pub enum ReviewQueueError {
+    /// The queue file couldn't be written or read
+    Io { path: PathBuf, source: io::Error },
+    /// A line isn't a valid queue item
+    Syntax { line: usize, message: String },
+}
+
+impl fmt::Display for ReviewQueueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewQueueError::Io { path, source } => {
+                write!(f, "failed to access {}: {}", path.display(), source)
+            }
+            ReviewQueueError::Syntax { line, message } => {
+                write!(f, "line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReviewQueueError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReviewQueueError::Io { source, .. } => Some(source),
+            ReviewQueueError::Syntax { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uncertainty::{decide_with_uncertainty, Uncertainty};
+    use crate::{Centimeters, Kilograms, Package, SortRules};
+
+    fn package(width: f64, height: f64, length: f64, mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(width),
+            Centimeters::new(height),
+            Centimeters::new(length),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_submit() {
+        let rules = SortRules::default();
+        let uncertainty = Uncertainty::new(Centimeters::new(1.0), Kilograms::new(0.5));
+        let mut queue = ReviewQueue::new();
+
+        assert!(!queue.submit("PKG-1", rules.decide(&package(50.0, 50.0, 50.0, 10.0))));
+        assert!(queue.submit("PKG-2", rules.decide(&package(50.0, 50.0, 0.0, 10.0))));
+        let near_limit =
+            decide_with_uncertainty(&rules, &package(149.5, 50.0, 50.0, 10.0), &uncertainty);
+        assert!(queue.submit_uncertain("PKG-3", near_limit));
+
+        let reasons: Vec<_> = queue.pending().iter().map(|i| i.reasons.clone()).collect();
+        assert_eq!(
+            reasons,
+            [vec![ReviewReason::Suspect], vec![ReviewReason::NearLimit]]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let rules = SortRules::default();
+        let mut queue = ReviewQueue::new();
+        queue.submit("PKG-1", rules.decide(&package(50.0, 50.0, 0.0, 10.0)));
+        queue.submit("PKG-2", rules.decide(&package(50.0, 50.0, -1.0, 30.0)));
+
+        let resolved = queue.reclassify("PKG-2", SortCategory::Rejected).unwrap();
+        assert_eq!(
+            resolved.resolution,
+            Resolution::Reclassified {
+                from: SortCategory::Special
+            }
+        );
+        assert_eq!(resolved.item.category, SortCategory::Rejected);
+
+        assert_eq!(
+            queue.approve("PKG-1").unwrap().resolution,
+            Resolution::Approved
+        );
+        assert!(queue.pending().is_empty());
+        assert_eq!(queue.resolved().len(), 2);
+        assert_eq!(
+            queue.approve("PKG-1").unwrap_err(),
+            ReviewError("PKG-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_queue_round_trip() {
+        let rules = SortRules::default();
+        let uncertainty = Uncertainty::new(Centimeters::new(1.0), Kilograms::new(0.5));
+        let mut queue = ReviewQueue::new();
+        queue.submit("PKG \"1\"", rules.decide(&package(50.0, 50.0, 0.0, 10.0)));
+        queue.submit("PKG-2", rules.decide(&package(50.0, 50.0, -1.0, 30.0)));
+        queue.submit_uncertain(
+            "PKG-3",
+            decide_with_uncertainty(&rules, &package(149.5, 50.0, 50.0, 10.0), &uncertainty),
+        );
+        queue.reclassify("PKG-2", SortCategory::Rejected).unwrap();
+        queue.approve("PKG-3").unwrap();
+
+        let mut saved = Vec::new();
+        queue.write(&mut saved).unwrap();
+        let loaded = ReviewQueue::read(&String::from_utf8(saved).unwrap()).unwrap();
+        assert_eq!(loaded.pending(), queue.pending());
+        assert_eq!(loaded.resolved(), queue.resolved());
+        assert_eq!(
+            loaded.pending()[0].suspect,
+            Some(SuspectMeasurement::NonPhysical)
+        );
+        assert!(queue.resolved()[0]
+            .to_json()
+            .contains(r#""status":"RECLASSIFIED","reclassified_from":"SPECIAL""#));
+        assert!(queue.pending()[0]
+            .to_json()
+            .contains(r#""status":"PENDING""#));
+    }
+
+    #[test]
+    fn test_queue_errors() {
+        assert!(ReviewQueue::read("\n \n").unwrap().pending().is_empty());
+        for (text, expected) in [
+            ("{", "line 1: invalid JSON"),
+            ("\n{}", "line 2: missing"),
+            (
+                r#"{"package_id":"A","category":"STANDARD","rule_version":"base","reasons":["LOST"],"suspect":null,"status":"PENDING","reclassified_from":null}"#,
+                "line 1: \"reasons\"",
+            ),
+        ] {
+            let err = ReviewQueue::read(text).unwrap_err();
+            assert!(err.to_string().starts_with(expected), "{}", err);
+        }
+        assert!(matches!(
+            ReviewQueue::load(Path::new("/nonexistent/review.jsonl")),
+            Err(ReviewQueueError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+//! Composite routing codes for the sortation controller.
+//!
+//! A routing code fuses the category, size class, handling flags and
+//! destination zone into one string, e.g. `SPC-L-HF-Z07`:
+//!
+//! | Part     | Values                                         |
+//! | -------- | ---------------------------------------------- |
+//! | Category | `STD`, `SPC`, `PER`, `REJ`                     |
+//! | Size     | `S`, `M`, `L`, `X` (see [`SizeClass`])         |
+//! | Flags    | `H` (hazmat) and/or `F` (fragile), or `0`      |
+//! | Zone     | Destination zone, ASCII letters and digits     |
+//!
+//! The encoding is stable: new values may be added, but existing ones keep
+//! their meaning.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Package, SortCategory, SortRules};
+
+/// Volume (cm³) under which a package is small
+pub const SMALL_VOLUME: f64 = 10_000.0;
+
+/// Volume (cm³) under which a package is medium
+pub const MEDIUM_VOLUME: f64 = 100_000.0;
+
+/// Size class of a package, used to pick chutes and totes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SizeClass {
+    /// Under 10,000 cm³
+    Small,
+    /// Under 100,000 cm³
+    Medium,
+    /// Larger, but not bulky
+    Large,
+    /// Bulky under the active rules
+    Oversize,
+}
+
+impl SizeClass {
+    /// Classifies a package's size under the given rules
+    pub fn of(package: &Package, rules: &SortRules) -> Self {
+        let volume = package.volume();
+        if rules.is_bulky(package) {
+            SizeClass::Oversize
+        } else if volume < SMALL_VOLUME {
+            SizeClass::Small
+        } else if volume < MEDIUM_VOLUME {
+            SizeClass::Medium
+        } else {
+            SizeClass::Large
+        }
+    }
+
+    /// Returns the one-letter code of the size class
+    pub fn code(&self) -> &'static str {
+        match self {
+            SizeClass::Small => "S",
+            SizeClass::Medium => "M",
+            SizeClass::Large => "L",
+            SizeClass::Oversize => "X",
+        }
+    }
+}
+
+/// Everything the sortation controller needs to route a package
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RoutingDecision {
+    pub category: SortCategory,
+    pub size_class: SizeClass,
+    pub hazmat: bool,
+    pub fragile: bool,
+    /// Destination zone
+    pub zone: String,
+}
+
+impl RoutingDecision {
+    /// Sorts a package under the rules and builds its routing decision for
+    /// the given destination zone
+    pub fn new(
+        package: &Package,
+        rules: &SortRules,
+        zone: impl Into<String>,
+    ) -> Result<Self, RoutingCodeError> {
+        let zone = zone.into();
+        if !is_valid_zone(&zone) {
+            return Err(RoutingCodeError(zone));
+        }
+
+        Ok(Self {
+            category: rules.decide(package).category,
+            size_class: SizeClass::of(package, rules),
+            hazmat: package.hazmat,
+            fragile: package.fragile,
+            zone,
+        })
+    }
+
+    /// Returns the routing code, e.g. `SPC-L-HF-Z07`
+    pub fn code(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for RoutingDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = match (self.hazmat, self.fragile) {
+            (true, true) => "HF",
+            (true, false) => "H",
+            (false, true) => "F",
+            (false, false) => "0",
+        };
+        write!(
+            f,
+            "{}-{}-{}-{}",
+            category_code(self.category),
+            self.size_class.code(),
+            flags,
+            self.zone
+        )
+    }
+}
+
+impl FromStr for RoutingDecision {
+    type Err = RoutingCodeError;
+
+    /// Parses a routing code, e.g. `SPC-L-HF-Z07`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || RoutingCodeError(s.to_string());
+        let parts: Vec<&str> = s.split('-').collect();
+        let [category, size, flags, zone] = parts.as_slice() else {
+            return Err(error());
+        };
+
+        let category = SortCategory::ALL
+            .into_iter()
+            .find(|c| category_code(*c) == *category)
+            .ok_or_else(error)?;
+        let size_class = [
+            SizeClass::Small,
+            SizeClass::Medium,
+            SizeClass::Large,
+            SizeClass::Oversize,
+        ]
+        .into_iter()
+        .find(|c| c.code() == *size)
+        .ok_or_else(error)?;
+        let (hazmat, fragile) = match *flags {
+            "HF" => (true, true),
+            "H" => (true, false),
+            "F" => (false, true),
+            "0" => (false, false),
+            _ => return Err(error()),
+        };
+        if !is_valid_zone(zone) {
+            return Err(error());
+        }
+
+        Ok(Self {
+            category,
+            size_class,
+            hazmat,
+            fragile,
+            zone: zone.to_string(),
+        })
+    }
+}
+
+/// Returns the three-letter routing code of a category
+fn category_code(category: SortCategory) -> &'static str {
+    match category {
+        SortCategory::Standard => "STD",
+        SortCategory::Special => "SPC",
+        SortCategory::Perishable => "PER",
+        SortCategory::Rejected => "REJ",
+    }
+}
+
+fn is_valid_zone(zone: &str) -> bool {
+    !zone.is_empty() && zone.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Error returned for an invalid routing code or destination zone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutingCodeError(String);
+
+impl fmt::Display for RoutingCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid routing code or zone: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for RoutingCodeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Centimeters, Kilograms};
+
+    fn package(width: f64, height: f64, length: f64, mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(width),
+            Centimeters::new(height),
+            Centimeters::new(length),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_routing_code() {
+        let rules = SortRules::default();
+
+        let routing = RoutingDecision::new(&package(20.0, 20.0, 20.0, 2.0), &rules, "Z07").unwrap();
+        assert_eq!(routing.size_class, SizeClass::Small);
+        assert_eq!(routing.code(), "STD-S-0-Z07");
+
+        let heavy = package(60.0, 50.0, 50.0, 25.0).with_hazmat().with_fragile();
+        let routing = RoutingDecision::new(&heavy, &rules, "NE1").unwrap();
+        assert_eq!(routing.code(), "SPC-L-HF-NE1");
+
+        let bulky = package(160.0, 50.0, 50.0, 10.0).with_fragile();
+        let routing = RoutingDecision::new(&bulky, &rules, "3").unwrap();
+        assert_eq!(routing.code(), "SPC-X-F-3");
+    }
+
+    #[test]
+    fn test_parse_routing_code() {
+        let routing: RoutingDecision = "PER-M-H-Z12".parse().unwrap();
+        assert_eq!(routing.category, SortCategory::Perishable);
+        assert_eq!(routing.size_class, SizeClass::Medium);
+        assert!(routing.hazmat);
+        assert!(!routing.fragile);
+        assert_eq!(routing.zone, "Z12");
+        assert_eq!(routing.to_string(), "PER-M-H-Z12");
+
+        assert!("PER-M-H".parse::<RoutingDecision>().is_err());
+        assert!("XXX-M-H-Z12".parse::<RoutingDecision>().is_err());
+        assert!("PER-M-H-Z-12".parse::<RoutingDecision>().is_err());
+        assert!(
+            RoutingDecision::new(&package(1.0, 1.0, 1.0, 1.0), &SortRules::default(), "").is_err()
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Numeric backing types for measurements.
+//!
+//! `Centimeters` and `Kilograms` are generic over a [`Scalar`]: `f64` by
+//! default, `f32` for embedded targets, or [`Fixed`] integer fixed-point.
+//! The rules always evaluate in `f64`, and converting with `to_f64` is exact,
+//! so the stored values are sorted as stored. Storing is not always exact:
+//! `f32` keeps about seven significant digits and `Fixed` three decimal
+//! places, so a measurement rounded on storage can land on the other side of
+//! a threshold (19.9999999 kg stored as `f32` is 20 kg, and heavy). `Fixed`
+//! has no NaN or infinity; converting those fails instead of producing a
+//! plausible-looking number.
+
+use std::fmt;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+    impl Sealed for super::Fixed {}
+}
+
+/// A numeric type measurements can be stored in. Sealed: implemented for
+/// `f32`, `f64` and [`Fixed`] only.
+pub trait Scalar: sealed::Sealed + Copy + PartialOrd + fmt::Debug {
+    /// Converts the value to `f64`
+    fn to_f64(self) -> f64;
+
+    /// Converts an `f64` to this type, rounding if needed. Returns `None` if
+    /// the type can't represent the value at all.
+    fn from_f64(value: f64) -> Option<Self>;
+}
+
+impl Scalar for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(value as f32)
+    }
+}
+
+impl Scalar for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(value)
+    }
+}
+
+/// Integer fixed-point number with three decimal places (thousandths)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Fixed(i64);
+
+impl Fixed {
+    /// Number of fixed-point units per whole unit
+    pub const SCALE: i64 = 1_000;
+
+    /// Creates a fixed-point number from thousandths, e.g. `1_500` for 1.5
+    pub fn from_milli(milli: i64) -> Self {
+        Self(milli)
+    }
+
+    /// Returns the value in thousandths
+    pub fn milli(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Scalar for Fixed {
+    fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+
+    /// Returns `None` for NaN, infinities and values beyond the `i64` range
+    fn from_f64(value: f64) -> Option<Self> {
+        let milli = (value * Self::SCALE as f64).round();
+        // `i64::MAX as f64` rounds up to 2^63, which is out of range
+        (milli >= i64::MIN as f64 && milli < i64::MAX as f64).then_some(Self(milli as i64))
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let scale = Self::SCALE as u64;
+        write!(f, "{}{}.{:03}", sign, abs / scale, abs % scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Centimeters, Kilograms, Package, SortRules};
+
+    #[test]
+    fn test_fixed() {
+        assert_eq!(Fixed::from_f64(1.2345), Some(Fixed::from_milli(1_235)));
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e300] {
+            assert_eq!(Fixed::from_f64(value), None);
+        }
+        assert_eq!(Fixed::from_milli(150_000).to_f64(), 150.0);
+        assert_eq!(Fixed::from_milli(-2_050).to_string(), "-2.050");
+    }
+
+    #[test]
+    fn test_same_decision_for_every_backing() {
+        fn decide<T: Scalar>(dims: [T; 3], mass: T) -> crate::SortCategory {
+            let [width, height, length] = dims.map(|d| Centimeters::new(d).to_f64());
+            let package = Package::new(width, height, length, Kilograms::new(mass).to_f64());
+            SortRules::default().decide(&package).category
+        }
+
+        for (dims, mass) in [
+            ([50.0, 50.0, 50.0], 10.0),
+            ([150.0, 10.0, 10.0], 10.0),
+            ([100.0, 100.0, 100.0], 20.0),
+            ([149.5, 149.5, 1.0], 19.75),
+        ] {
+            let expected = decide(dims, mass);
+            assert_eq!(decide(dims.map(|d| d as f32), mass as f32), expected);
+            let fixed = |value| Fixed::from_f64(value).unwrap();
+            assert_eq!(decide(dims.map(fixed), fixed(mass)), expected);
+        }
+    }
+
+    #[test]
+    fn test_storage_rounding() {
+        // Rounded up to 20 kg on storage, so heavy once stored as `f32`
+        let package = |mass| {
+            let side = Centimeters::new(10.0);
+            Package::new(side, side, side, mass)
+        };
+        let mass = 19.999_999_9;
+        assert!(!package(Kilograms::new(mass)).is_heavy());
+        let stored = Kilograms::new(f32::from_f64(mass).unwrap());
+        assert!(package(stored.to_f64()).is_heavy());
+    }
+}
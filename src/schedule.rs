@@ -0,0 +1,250 @@
+//! Time-based rule overrides, such as stricter limits during peak season.
+//!
+//! Dates and times are evaluated in UTC.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{Centimeters, Kilograms, SortRules};
+
+/// A calendar date (UTC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Creates a date, returning `None` if it doesn't exist
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        (day >= 1 && day <= days_in_month(year, month)).then_some(Self { year, month, day })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseTimeError;
+
+    /// Parses an ISO 8601 date (`YYYY-MM-DD`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseTimeError(s.to_string());
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(error);
+        let (year, month, day) = (next()?, next()?, next()?);
+
+        Date::new(
+            year.parse().map_err(|_| error())?,
+            month.parse().map_err(|_| error())?,
+            day.parse().map_err(|_| error())?,
+        )
+        .ok_or_else(error)
+    }
+}
+
+/// A time of day (UTC), to the minute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeOfDay {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl TimeOfDay {
+    /// Creates a time of day, returning `None` if it doesn't exist
+    pub fn new(hour: u8, minute: u8) -> Option<Self> {
+        (hour < 24 && minute < 60).then_some(Self { hour, minute })
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = ParseTimeError;
+
+    /// Parses a 24-hour time (`HH:MM`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseTimeError(s.to_string());
+        let (hour, minute) = s.trim().split_once(':').ok_or_else(error)?;
+
+        TimeOfDay::new(
+            hour.parse().map_err(|_| error())?,
+            minute.parse().map_err(|_| error())?,
+        )
+        .ok_or_else(error)
+    }
+}
+
+/// Error returned when a date or time can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTimeError(String);
+
+impl fmt::Display for ParseTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid date or time: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseTimeError {}
+
+/// When a rule override is in effect. Both conditions must hold; a missing
+/// condition always holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Schedule {
+    /// Inclusive range of dates
+    pub dates: Option<(Date, Date)>,
+    /// Range of times of day, including the start but not the end. A range
+    /// whose end is before its start wraps past midnight.
+    pub hours: Option<(TimeOfDay, TimeOfDay)>,
+}
+
+impl Schedule {
+    /// Checks if the schedule is in effect at the given time
+    pub fn is_active(&self, time: SystemTime) -> bool {
+        let (date, time_of_day) = civil_time(time);
+
+        let in_dates = self
+            .dates
+            .is_none_or(|(start, end)| start <= date && date <= end);
+        let in_hours = self.hours.is_none_or(|(start, end)| {
+            if start <= end {
+                start <= time_of_day && time_of_day < end
+            } else {
+                time_of_day >= start || time_of_day < end
+            }
+        });
+
+        in_dates && in_hours
+    }
+}
+
+/// A named set of rule changes that applies only while its schedule is in
+/// effect
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RuleOverride {
+    pub name: String,
+    pub schedule: Schedule,
+    pub volume_threshold: Option<f64>,
+    pub dimension_threshold: Option<Centimeters>,
+    pub mass_threshold: Option<Kilograms>,
+}
+
+impl RuleOverride {
+    /// Applies the override's changes to a rule set
+    pub fn apply(&self, rules: &mut SortRules) {
+        if let Some(volume) = self.volume_threshold {
+            rules.volume_threshold = volume;
+        }
+        if let Some(dimension) = self.dimension_threshold {
+            rules.dimension_threshold = dimension;
+        }
+        if let Some(mass) = self.mass_threshold {
+            rules.mass_threshold = mass;
+        }
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+/// Converts a system time to a UTC date and time of day
+fn civil_time(time: SystemTime) -> (Date, TimeOfDay) {
+    let seconds = match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_secs() as i64,
+        Err(before) => -(before.duration().as_secs_f64().ceil() as i64),
+    };
+    let days = seconds.div_euclid(86_400);
+    let second_of_day = seconds.rem_euclid(86_400);
+
+    // Days since 1970-01-01 to a proleptic Gregorian date
+    // (http://howardhinnant.github.io/date_algorithms.html#civil_from_days)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (
+        Date {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+        },
+        TimeOfDay {
+            hour: (second_of_day / 3_600) as u8,
+            minute: (second_of_day % 3_600 / 60) as u8,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// 2024-11-29T14:30:00Z
+    fn black_friday_afternoon() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_732_890_600)
+    }
+
+    #[test]
+    fn test_civil_time() {
+        let (date, time) = civil_time(black_friday_afternoon());
+        assert_eq!(date, Date::new(2024, 11, 29).unwrap());
+        assert_eq!(time, TimeOfDay::new(14, 30).unwrap());
+
+        let (date, _) = civil_time(UNIX_EPOCH - Duration::from_secs(1));
+        assert_eq!(date, Date::new(1969, 12, 31).unwrap());
+    }
+
+    #[test]
+    fn test_parse_date_and_time() {
+        assert_eq!("2024-02-29".parse(), Ok(Date::new(2024, 2, 29).unwrap()));
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2024-13-01".parse::<Date>().is_err());
+        assert_eq!("06:05".parse(), Ok(TimeOfDay::new(6, 5).unwrap()));
+        assert!("24:00".parse::<TimeOfDay>().is_err());
+    }
+
+    #[test]
+    fn test_schedule() {
+        let peak = Schedule {
+            dates: Some(("2024-11-25".parse().unwrap(), "2024-12-24".parse().unwrap())),
+            hours: None,
+        };
+        assert!(peak.is_active(black_friday_afternoon()));
+        assert!(!peak.is_active(UNIX_EPOCH));
+
+        // Night shift wraps past midnight
+        let nights = Schedule {
+            dates: None,
+            hours: Some(("22:00".parse().unwrap(), "06:00".parse().unwrap())),
+        };
+        assert!(!nights.is_active(black_friday_afternoon()));
+        assert!(nights.is_active(UNIX_EPOCH));
+    }
+}
@@ -0,0 +1,159 @@
+//! Boundary-case self-test of a rule set, for verifying a deployment before
+//! going live.
+//!
+//! The cases are derived from the loaded rules, so a site with its own
+//! thresholds checks its own boundaries: a package exactly at a threshold
+//! must meet the rule, one just below it must not, and non-physical
+//! measurements must be flagged for review.
+
+use std::fmt;
+
+use crate::{Centimeters, Kilograms, Package, SortDecision, SortRules, SuspectMeasurement};
+
+/// How far below a threshold the "just below" cases sit
+const MARGIN: f64 = 0.01;
+
+/// The outcome of one boundary case
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    /// What the case checks, e.g. "length at 150 cm is bulky"
+    pub name: String,
+    pub passed: bool,
+}
+
+/// The outcome of every boundary case
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTestReport {
+    pub checks: Vec<Check>,
+}
+
+impl SelfTestReport {
+    /// Whether every case passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    /// Formats one `PASS`/`FAIL` line per case and a summary line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = if check.passed { "PASS" } else { "FAIL" };
+            writeln!(f, "{} {}", status, check.name)?;
+        }
+        let passed = self.checks.iter().filter(|check| check.passed).count();
+        write!(f, "{}/{} checks passed", passed, self.checks.len())
+    }
+}
+
+/// Runs the built-in boundary cases against the rules
+pub fn run(rules: &SortRules) -> SelfTestReport {
+    let mut checks = Vec::new();
+    let mut check = |name: String, package: Package, expect: fn(&SortDecision) -> bool| {
+        let passed = expect(&rules.decide(&package));
+        checks.push(Check { name, passed });
+    };
+
+    let length = rules
+        .axis_limits
+        .map_or(rules.dimension_threshold, |limits| limits.length)
+        .value();
+    check(
+        format!("length at {} cm is bulky", length),
+        package(length, 1.0, 1.0, 0.1),
+        |d| d.bulky,
+    );
+    check(
+        format!("length just below {} cm is not bulky", length),
+        package(length - MARGIN, 1.0, 1.0, 0.1),
+        |d| !d.bulky,
+    );
+
+    // Sides that are powers of two keep the volume exact
+    let volume = rules.volume_threshold;
+    let side = 2f64.powi(volume.cbrt().log2().round() as i32);
+    check(
+        format!("volume at {} cm³ is bulky", volume),
+        package(side, side, volume / (side * side), 0.1),
+        |d| d.bulky,
+    );
+
+    let mass = rules.mass_threshold.value();
+    check(
+        format!("mass at {} kg is heavy", mass),
+        package(30.0, 30.0, 30.0, mass),
+        |d| d.heavy,
+    );
+    check(
+        format!("mass just below {} kg is not heavy", mass),
+        package(30.0, 30.0, 30.0, mass - MARGIN),
+        |d| !d.heavy,
+    );
+
+    if let Some(max_mass) = rules.max_mass {
+        check(
+            format!("mass at {} kg is overweight", max_mass.value()),
+            package(30.0, 30.0, 30.0, max_mass.value()),
+            |d| d.overweight,
+        );
+    }
+
+    check(
+        "NaN width is flagged non-physical".to_string(),
+        package(f64::NAN, 30.0, 30.0, 1.0),
+        |d| d.suspect == Some(SuspectMeasurement::NonPhysical) && d.needs_review,
+    );
+    check(
+        "NaN mass is flagged non-physical".to_string(),
+        package(30.0, 30.0, 30.0, f64::NAN),
+        |d| d.suspect == Some(SuspectMeasurement::NonPhysical) && d.needs_review,
+    );
+
+    SelfTestReport { checks }
+}
+
+fn package(w: f64, h: f64, l: f64, m: f64) -> Package {
+    Package::new(
+        Centimeters::new(w),
+        Centimeters::new(h),
+        Centimeters::new(l),
+        Kilograms::new(m),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AxisLimits;
+
+    #[test]
+    fn test_default_rules_pass() {
+        let report = run(&SortRules::default());
+        assert!(report.passed(), "{}", report);
+        assert!(report
+            .to_string()
+            .contains("PASS length at 150 cm is bulky"));
+        assert!(report
+            .to_string()
+            .contains("PASS volume at 1000000 cm³ is bulky"));
+        assert!(report.to_string().ends_with("7/7 checks passed"));
+    }
+
+    #[test]
+    fn test_site_rules_pass() {
+        let rules = SortRules {
+            volume_threshold: 750_000.0,
+            mass_threshold: Kilograms::new(25.5),
+            max_mass: Some(Kilograms::new(70.0)),
+            axis_limits: Some(AxisLimits::new(
+                Centimeters::new(200.0),
+                Centimeters::new(150.0),
+                Centimeters::new(150.0),
+            )),
+            ..SortRules::default()
+        };
+        let report = run(&rules);
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.checks.len(), 8);
+    }
+}
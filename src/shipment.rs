@@ -0,0 +1,121 @@
+use crate::{Kilograms, Package, SortCategory};
+
+/// Aggregate limits applied to a shipment as a whole
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShipmentLimits {
+    /// Total mass at which the shipment requires special handling
+    pub heavy_total_mass: Kilograms,
+    /// Total mass above which the shipment cannot be processed
+    pub max_total_mass: Kilograms,
+    /// Maximum number of pieces a single shipment may contain
+    pub max_pieces: usize,
+}
+
+impl Default for ShipmentLimits {
+    fn default() -> Self {
+        Self {
+            heavy_total_mass: Kilograms::new(70.0),
+            max_total_mass: Kilograms::new(500.0),
+            max_pieces: 50,
+        }
+    }
+}
+
+/// A multi-piece shipment: several packages travelling under one id
+#[derive(Debug, Clone)]
+pub struct Shipment {
+    pub id: String,
+    pub pieces: Vec<Package>,
+}
+
+impl Shipment {
+    /// Creates a new shipment with the given id and pieces
+    pub fn new(id: impl Into<String>, pieces: Vec<Package>) -> Self {
+        Self {
+            id: id.into(),
+            pieces,
+        }
+    }
+
+    /// Returns the number of pieces in the shipment
+    pub fn piece_count(&self) -> usize {
+        self.pieces.len()
+    }
+
+    /// Calculates the combined mass of all pieces
+    pub fn total_mass(&self) -> Kilograms {
+        Kilograms::new(self.pieces.iter().map(|p| p.mass.value()).sum())
+    }
+
+    /// Returns the most severe category of any single piece
+    /// (`None` for an empty shipment)
+    pub fn worst_piece_category(&self) -> Option<SortCategory> {
+        self.pieces.iter().map(Package::sort_category).max()
+    }
+
+    /// Determines the shipment-level category.
+    ///
+    /// The shipment is at least as severe as its worst piece. On top of that:
+    /// - exceeding `max_pieces` or `max_total_mass` rejects the shipment
+    /// - reaching `heavy_total_mass` makes it at least SPECIAL
+    pub fn sort_category(&self, limits: &ShipmentLimits) -> SortCategory {
+        let worst = self
+            .worst_piece_category()
+            .unwrap_or(SortCategory::Standard);
+        let total_mass = self.total_mass();
+
+        let aggregate =
+            if self.piece_count() > limits.max_pieces || total_mass > limits.max_total_mass {
+                SortCategory::Rejected
+            } else if total_mass >= limits.heavy_total_mass {
+                SortCategory::Special
+            } else {
+                SortCategory::Standard
+            };
+
+        worst.max(aggregate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Centimeters;
+
+    fn piece(mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(30.0),
+            Centimeters::new(30.0),
+            Centimeters::new(30.0),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_shipment_worst_piece() {
+        let shipment = Shipment::new("S-1", vec![piece(5.0), piece(25.0), piece(5.0)]);
+        assert_eq!(shipment.worst_piece_category(), Some(SortCategory::Special));
+        assert_eq!(
+            shipment.sort_category(&ShipmentLimits::default()),
+            SortCategory::Special
+        );
+    }
+
+    #[test]
+    fn test_shipment_aggregate_thresholds() {
+        let limits = ShipmentLimits::default();
+
+        // Every piece is standard, but together they are heavy
+        let shipment = Shipment::new("S-2", vec![piece(15.0); 5]);
+        assert_eq!(shipment.total_mass(), Kilograms::new(75.0));
+        assert_eq!(shipment.sort_category(&limits), SortCategory::Special);
+
+        // Too many pieces
+        let shipment = Shipment::new("S-3", vec![piece(1.0); 51]);
+        assert_eq!(shipment.sort_category(&limits), SortCategory::Rejected);
+
+        // Empty shipments are standard
+        let shipment = Shipment::new("S-4", Vec::new());
+        assert_eq!(shipment.sort_category(&limits), SortCategory::Standard);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{Centimeters, Kilograms, Package, Shape, SortCategory, SortRules};
+
+/// The smallest split of a rejected package that can be processed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitRecommendation {
+    /// Number of pieces to split the package into
+    pub pieces: u32,
+    /// Estimated dimensions and mass of each piece
+    pub piece: Package,
+    /// Category each piece would be sorted into
+    pub category: SortCategory,
+}
+
+/// Estimates one of `pieces` equal pieces of a package.
+///
+/// The contents are assumed to be cut evenly along the longest dimension,
+/// with the mass shared equally between pieces. Cylinders cut along their
+/// length stay cylinders; any other cut of a non-box shape gives an irregular
+/// piece described by its bounding box. Handling attributes carry over, and
+/// the estimate is returned [normalized](Package::normalized).
+pub fn estimate_piece(package: &Package, pieces: u32) -> Package {
+    let n = f64::from(pieces.max(1));
+    let whole = package.normalized();
+    let cut = Centimeters::new(whole.length.value() / n);
+    let mass = Kilograms::new(package.mass.value() / n);
+
+    let piece = match package.shape {
+        Shape::Box => Package::new(whole.width, whole.height, cut, mass),
+        Shape::Cylinder { diameter, length } if length >= diameter => {
+            Package::cylinder(diameter, Centimeters::new(length.value() / n), mass)
+        }
+        Shape::Cylinder { .. } | Shape::Irregular { .. } => {
+            Package::irregular([whole.width, whole.height, cut], mass)
+        }
+    };
+    Package {
+        perishable: package.perishable,
+        fragile: package.fragile,
+        hazmat: package.hazmat,
+        ..piece
+    }
+    .normalized()
+}
+
+/// Finds the minimal number of pieces (up to `max_pieces`) a package the
+/// rules REJECT must be split into so that every piece is SPECIAL or STANDARD
+/// under the same rules.
+///
+/// Returns `None` if the package is not rejected, or if no split within
+/// `max_pieces` makes it processable.
+pub fn recommend_split(
+    rules: &SortRules,
+    package: &Package,
+    max_pieces: u32,
+) -> Option<SplitRecommendation> {
+    if rules.decide(package).category != SortCategory::Rejected {
+        return None;
+    }
+
+    (2..=max_pieces).find_map(|pieces| {
+        let piece = estimate_piece(package, pieces);
+        let category = rules.decide(&piece).category;
+        matches!(category, SortCategory::Standard | SortCategory::Special).then_some(
+            SplitRecommendation {
+                pieces,
+                piece,
+                category,
+            },
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Celsius, TemperatureRange};
+
+    #[test]
+    fn test_recommend_split() {
+        // Bulky by dimension and heavy: halving the length and mass is enough
+        let package = Package::new(
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Centimeters::new(160.0),
+            Kilograms::new(30.0),
+        );
+        let split = recommend_split(&SortRules::default(), &package, 10).unwrap();
+        assert_eq!(split.pieces, 2);
+        assert_eq!(split.piece.length, Centimeters::new(80.0));
+        assert_eq!(split.piece.mass, Kilograms::new(15.0));
+        assert_eq!(split.category, SortCategory::Standard);
+    }
+
+    #[test]
+    fn test_estimate_piece() {
+        // Quartering the long side makes it shorter than the others
+        let package = Package::new(
+            Centimeters::new(160.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Kilograms::new(8.0),
+        )
+        .with_fragile();
+        let piece = estimate_piece(&package, 4);
+        assert_eq!(
+            [piece.length, piece.width, piece.height],
+            [50.0, 50.0, 40.0].map(Centimeters::new)
+        );
+        assert_eq!(piece.mass, Kilograms::new(2.0));
+        assert!(piece.fragile);
+
+        // A cylinder cut along its length keeps its cross-section
+        let carpet = Package::cylinder(
+            Centimeters::new(120.0),
+            Centimeters::new(140.0),
+            Kilograms::new(30.0),
+        );
+        let piece = estimate_piece(&carpet, 2);
+        assert!((piece.volume() - carpet.volume() / 2.0).abs() < 1e-6);
+
+        // A disc cut across its diameter no longer is a cylinder
+        let disc = Package::cylinder(
+            Centimeters::new(120.0),
+            Centimeters::new(10.0),
+            Kilograms::new(30.0),
+        );
+        let piece = estimate_piece(&disc, 2);
+        assert!(matches!(piece.shape, Shape::Irregular { .. }));
+        assert_eq!(piece.volume(), 120.0 * 60.0 * 10.0);
+    }
+
+    #[test]
+    fn test_recommend_split_cylinder() {
+        // Bulky and heavy; two halves are under the volume limit
+        let carpet = Package::cylinder(
+            Centimeters::new(100.0),
+            Centimeters::new(200.0),
+            Kilograms::new(30.0),
+        );
+        let split = recommend_split(&SortRules::default(), &carpet, 10).unwrap();
+        assert_eq!(split.pieces, 2);
+        assert_eq!(split.category, SortCategory::Standard);
+    }
+
+    #[test]
+    fn test_recommend_split_not_needed_or_impossible() {
+        // Standard packages don't need splitting
+        let package = Package::new(
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Kilograms::new(1.0),
+        );
+        assert_eq!(recommend_split(&SortRules::default(), &package, 10), None);
+
+        // Too wide on two axes to be fixed by cutting along one, and too
+        // heavy for four pieces to drop under the heavy limit
+        let package = Package::new(
+            Centimeters::new(200.0),
+            Centimeters::new(200.0),
+            Centimeters::new(10.0),
+            Kilograms::new(100.0),
+        );
+        assert_eq!(recommend_split(&SortRules::default(), &package, 4), None);
+
+        // Perishable pieces go to the cold lane, which isn't a processable
+        // split
+        let package = Package::new(
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Centimeters::new(160.0),
+            Kilograms::new(30.0),
+        )
+        .with_temperature_range(TemperatureRange::new(Celsius::new(2.0), Celsius::new(8.0)));
+        assert_eq!(recommend_split(&SortRules::default(), &package, 10), None);
+    }
+
+    #[test]
+    fn test_recommend_split_site_rules() {
+        // Standard by default, but rejected under a 60 kg cap; each piece
+        // must be under the cap and the 25 kg heavy limit
+        let rules = SortRules {
+            mass_threshold: Kilograms::new(25.0),
+            max_mass: Some(Kilograms::new(60.0)),
+            ..SortRules::default()
+        };
+        let package = Package::new(
+            Centimeters::new(40.0),
+            Centimeters::new(40.0),
+            Centimeters::new(60.0),
+            Kilograms::new(70.0),
+        );
+        assert_eq!(recommend_split(&SortRules::default(), &package, 10), None);
+
+        let split = recommend_split(&rules, &package, 10).unwrap();
+        assert_eq!(split.pieces, 2);
+        assert_eq!(split.category, SortCategory::Special);
+    }
+}
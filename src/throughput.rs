@@ -0,0 +1,265 @@
+//! Line throughput estimates for shift planning.
+//!
+//! The model is a single induction belt feeding one divert per category.
+//! Every package occupies its longest side plus the configured spacing on the
+//! belt, and each divert handles one package at a time. A divert that needs
+//! longer for its packages than the belt takes to deliver them holds the
+//! line, so the batch takes as long as the slowest of the belt and the
+//! diverts. Packages with a non-physical dimension (not a positive, finite
+//! number) can't be placed on the belt model and are left out, counted in
+//! [`ThroughputEstimate::skipped`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use crate::{Centimeters, Package, SortCategory};
+
+/// Speed, spacing and divert timings of a sorting line
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineParameters {
+    belt_speed: f64,
+    spacing: Centimeters,
+    divert_times: HashMap<SortCategory, Duration>,
+}
+
+impl LineParameters {
+    /// Creates line parameters for a belt running at `belt_speed` meters per
+    /// second with `spacing` between consecutive packages. Divert times start
+    /// at zero.
+    pub fn new(belt_speed: f64, spacing: Centimeters) -> Result<Self, LineParametersError> {
+        if !(belt_speed.is_finite() && belt_speed > 0.0) {
+            return Err(LineParametersError(format!(
+                "belt speed must be a positive number of m/s, got {}",
+                belt_speed
+            )));
+        }
+        if !(spacing.value().is_finite() && spacing.value() >= 0.0) {
+            return Err(LineParametersError(format!(
+                "spacing must be a non-negative number of cm, got {}",
+                spacing.value()
+            )));
+        }
+        Ok(Self {
+            belt_speed,
+            spacing,
+            divert_times: HashMap::new(),
+        })
+    }
+
+    /// Sets how long the divert of a category is busy per package
+    pub fn set_divert_time(&mut self, category: SortCategory, time: Duration) {
+        self.divert_times.insert(category, time);
+    }
+
+    /// Belt speed in meters per second
+    pub fn belt_speed(&self) -> f64 {
+        self.belt_speed
+    }
+
+    /// Gap between consecutive packages on the belt
+    pub fn spacing(&self) -> Centimeters {
+        self.spacing
+    }
+
+    /// How long the divert of a category is busy per package
+    pub fn divert_time(&self, category: SortCategory) -> Duration {
+        self.divert_times
+            .get(&category)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Error returned when line parameters are out of range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineParametersError(String);
+
+impl fmt::Display for LineParametersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid line parameters: {}", self.0)
+    }
+}
+
+impl std::error::Error for LineParametersError {}
+
+/// What limits the throughput of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bottleneck {
+    /// The belt delivers packages no faster than the diverts clear them
+    Belt,
+    /// The divert of this category needs the longest, holding the line
+    Divert(SortCategory),
+}
+
+/// Piece count and divert busy time of one category
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CategoryLoad {
+    pub category: SortCategory,
+    pub pieces: usize,
+    /// Total time the divert of the category is busy
+    pub divert_time: Duration,
+}
+
+/// Estimated processing time of a classified batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThroughputEstimate {
+    /// Packages included in the estimate
+    pub pieces: usize,
+    /// Packages left out because of non-physical measurements
+    pub skipped: usize,
+    /// Time the belt needs to deliver every package at full speed
+    pub belt_time: Duration,
+    /// Load per category, in `SortCategory::ALL` order
+    pub loads: Vec<CategoryLoad>,
+    /// Estimated time to process the whole batch
+    pub total_time: Duration,
+    pub bottleneck: Bottleneck,
+}
+
+impl ThroughputEstimate {
+    /// Sustained throughput of the batch in pieces per hour
+    pub fn pieces_per_hour(&self) -> f64 {
+        if self.total_time.is_zero() {
+            return 0.0;
+        }
+        self.pieces as f64 * 3600.0 / self.total_time.as_secs_f64()
+    }
+}
+
+/// Estimates how long a line takes to process a classified batch, and which
+/// part of the line limits it
+pub fn estimate(batch: &[(Package, SortCategory)], line: &LineParameters) -> ThroughputEstimate {
+    let physical = |package: &Package| {
+        [package.width, package.height, package.length]
+            .iter()
+            .all(|d| d.value().is_finite() && d.value() > 0.0)
+    };
+    let placed: Vec<(f64, SortCategory)> = batch
+        .iter()
+        .filter(|(package, _)| physical(package))
+        .map(|(package, category)| (package.normalized().length.value(), *category))
+        .collect();
+    let belt_length: f64 = placed
+        .iter()
+        .map(|(length, _)| length + line.spacing.value())
+        .sum();
+    // Saturates for batches too long to represent
+    let belt_time =
+        Duration::try_from_secs_f64(belt_length / 100.0 / line.belt_speed).unwrap_or(Duration::MAX);
+
+    let loads: Vec<CategoryLoad> = SortCategory::ALL
+        .into_iter()
+        .map(|category| {
+            let pieces = placed.iter().filter(|(_, c)| *c == category).count();
+            let divert_time = u32::try_from(pieces)
+                .ok()
+                .and_then(|pieces| line.divert_time(category).checked_mul(pieces))
+                .unwrap_or(Duration::MAX);
+            CategoryLoad {
+                category,
+                pieces,
+                divert_time,
+            }
+        })
+        .collect();
+
+    let slowest = loads
+        .iter()
+        .filter(|load| load.divert_time > belt_time)
+        .max_by_key(|load| load.divert_time);
+    let (total_time, bottleneck) = match slowest {
+        Some(load) => (load.divert_time, Bottleneck::Divert(load.category)),
+        None => (belt_time, Bottleneck::Belt),
+    };
+
+    ThroughputEstimate {
+        pieces: placed.len(),
+        skipped: batch.len() - placed.len(),
+        belt_time,
+        loads,
+        total_time,
+        bottleneck,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Kilograms;
+
+    fn package(w: f64, h: f64, l: f64, m: f64) -> Package {
+        Package::new(
+            Centimeters::new(w),
+            Centimeters::new(h),
+            Centimeters::new(l),
+            Kilograms::new(m),
+        )
+    }
+
+    fn line() -> LineParameters {
+        // 2 m/s with 50 cm gaps: a 50 cm package takes half a second
+        let mut line = LineParameters::new(2.0, Centimeters::new(50.0)).unwrap();
+        line.set_divert_time(SortCategory::Standard, Duration::from_millis(400));
+        line.set_divert_time(SortCategory::Special, Duration::from_secs(3));
+        line
+    }
+
+    #[test]
+    fn test_belt_bottleneck() {
+        let batch = vec![(package(30.0, 20.0, 50.0, 5.0), SortCategory::Standard); 10];
+        let estimate = estimate(&batch, &line());
+
+        assert_eq!(estimate.belt_time, Duration::from_secs(5));
+        assert_eq!(estimate.total_time, Duration::from_secs(5));
+        assert_eq!(estimate.bottleneck, Bottleneck::Belt);
+        assert_eq!(estimate.loads[0].pieces, 10);
+        assert_eq!(estimate.loads[0].divert_time, Duration::from_secs(4));
+        assert_eq!(estimate.pieces_per_hour(), 7200.0);
+    }
+
+    #[test]
+    fn test_divert_bottleneck() {
+        let mut batch = vec![(package(30.0, 20.0, 50.0, 5.0), SortCategory::Standard); 8];
+        batch.extend([(package(160.0, 50.0, 50.0, 10.0), SortCategory::Special); 3]);
+        let estimate = estimate(&batch, &line());
+
+        // 8 x 1 m + 3 x 2.1 m of belt at 2 m/s, but the special divert needs 9 s
+        assert_eq!(estimate.belt_time, Duration::from_secs_f64(7.15));
+        assert_eq!(estimate.total_time, Duration::from_secs(9));
+        assert_eq!(
+            estimate.bottleneck,
+            Bottleneck::Divert(SortCategory::Special)
+        );
+        assert_eq!(estimate.pieces, 11);
+    }
+
+    #[test]
+    fn test_line_parameters_errors() {
+        assert!(LineParameters::new(0.0, Centimeters::new(50.0)).is_err());
+        assert!(LineParameters::new(f64::NAN, Centimeters::new(50.0)).is_err());
+        assert!(LineParameters::new(2.0, Centimeters::new(-1.0)).is_err());
+
+        let estimate = estimate(&[], &line());
+        assert_eq!(estimate.total_time, Duration::ZERO);
+        assert_eq!(estimate.bottleneck, Bottleneck::Belt);
+        assert_eq!(estimate.pieces_per_hour(), 0.0);
+    }
+
+    #[test]
+    fn test_non_physical_packages_skipped() {
+        let batch = [
+            (package(f64::NAN, 20.0, 50.0, 5.0), SortCategory::Standard),
+            (
+                package(f64::INFINITY, 20.0, 50.0, 5.0),
+                SortCategory::Standard,
+            ),
+            (package(-50.0, 20.0, 50.0, 5.0), SortCategory::Standard),
+            (package(30.0, 20.0, 50.0, 5.0), SortCategory::Standard),
+        ];
+        let estimate = estimate(&batch, &line());
+        assert_eq!((estimate.pieces, estimate.skipped), (1, 3));
+        assert_eq!(estimate.belt_time, Duration::from_millis(500));
+        assert_eq!(estimate.loads[0].pieces, 1);
+    }
+}
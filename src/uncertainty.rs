@@ -0,0 +1,277 @@
+//! Classification that accounts for measurement uncertainty, so borderline
+//! packages can be sent to a re-measure station.
+
+use crate::{Centimeters, Kilograms, Package, Shape, SortCategory, SortDecision, SortRules};
+
+/// How far each measurement may be off (±)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uncertainty {
+    /// Uncertainty of every dimension
+    pub dimension: Centimeters,
+    /// Uncertainty of the mass
+    pub mass: Kilograms,
+}
+
+impl Uncertainty {
+    /// Creates a new uncertainty from the ± of each dimension and of the mass
+    pub fn new(dimension: Centimeters, mass: Kilograms) -> Self {
+        Self { dimension, mass }
+    }
+}
+
+impl Default for Uncertainty {
+    /// Exact measurements
+    fn default() -> Self {
+        Self::new(Centimeters::new(0.0), Kilograms::new(0.0))
+    }
+}
+
+/// How sure the classification is, given the measurement uncertainty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    /// Every measurement within the uncertainty gives the same category
+    Certain,
+    /// The package is close enough to a limit that it could belong to
+    /// another category
+    Borderline,
+}
+
+/// A decision together with every category the package could really be in
+#[derive(Debug, Clone, PartialEq)]
+pub struct UncertainDecision {
+    /// The decision for the measurements as taken
+    pub decision: SortDecision,
+    /// Every category reachable within the uncertainty, least severe first
+    pub possible: Vec<SortCategory>,
+    pub confidence: Confidence,
+}
+
+impl UncertainDecision {
+    /// Checks if the package should be measured again before sorting
+    pub fn needs_remeasure(&self) -> bool {
+        self.confidence == Confidence::Borderline
+    }
+}
+
+/// Sorts a package and works out which categories it could belong to if each
+/// measurement is off by up to the given uncertainty.
+///
+/// Apart from the perishable flag, the category depends only on whether the
+/// package is bulky, too small, heavy, overweight or dense, and each of those
+/// switches at most once as the dimensions or the mass grow. The range of
+/// possible measurements is cut at every point where a dimension-only or
+/// mass-only rule switches, so those rules are constant within each part;
+/// only the dense rule still varies there, and it is checked at the densest
+/// and the lightest corner of each part. This finds every reachable category,
+/// including ones in the middle of the range (e.g. a package that is too
+/// small at its smallest and bulky at its largest, but standard in between).
+pub fn decide_with_uncertainty(
+    rules: &SortRules,
+    package: &Package,
+    uncertainty: &Uncertainty,
+) -> UncertainDecision {
+    let (dimension, mass) = (uncertainty.dimension.value(), uncertainty.mass.value());
+
+    let dimension_parts = parts(
+        -dimension,
+        dimension,
+        &[
+            &|shift| rules.is_bulky(&adjusted(package, shift, 0.0)),
+            &|shift| rules.is_too_small(&adjusted(package, shift, 0.0)),
+        ],
+    );
+    let mass_parts = parts(
+        -mass,
+        mass,
+        &[
+            &|shift| rules.is_heavy(&adjusted(package, 0.0, shift)),
+            &|shift| rules.is_overweight(&adjusted(package, 0.0, shift)),
+        ],
+    );
+
+    let mut possible = Vec::new();
+    for &(smallest, largest) in &dimension_parts {
+        for &(lightest, heaviest) in &mass_parts {
+            for (dimension, mass) in [(smallest, heaviest), (largest, lightest)] {
+                possible.push(rules.decide(&adjusted(package, dimension, mass)).category);
+            }
+        }
+    }
+    possible.sort();
+    possible.dedup();
+
+    let confidence = if possible.len() == 1 {
+        Confidence::Certain
+    } else {
+        Confidence::Borderline
+    };
+
+    UncertainDecision {
+        decision: rules.decide(package),
+        possible,
+        confidence,
+    }
+}
+
+/// Splits `[low, high]` into parts within which none of the tests changes,
+/// given that each changes at most once. Returns the first and last value of
+/// each part.
+fn parts(low: f64, high: f64, tests: &[&dyn Fn(f64) -> bool]) -> Vec<(f64, f64)> {
+    let mut cuts: Vec<f64> = tests
+        .iter()
+        .filter_map(|test| crossing(low, high, test))
+        .collect();
+    cuts.sort_by(f64::total_cmp);
+    cuts.dedup();
+
+    let starts = std::iter::once(low).chain(cuts.iter().copied());
+    let ends = cuts.iter().map(|cut| cut.next_down()).chain([high]);
+    starts.zip(ends).collect()
+}
+
+/// Finds the first value in `(low, high]` where the test gives a different
+/// result than at `low`, by bisection
+fn crossing(mut low: f64, mut high: f64, test: impl Fn(f64) -> bool) -> Option<f64> {
+    let initial = test(low);
+    if test(high) == initial {
+        return None;
+    }
+    loop {
+        let middle = low + (high - low) / 2.0;
+        // Also stops on adjacent or non-finite bounds
+        if !(middle > low && middle < high) {
+            return Some(high);
+        }
+        if test(middle) == initial {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+}
+
+/// Returns the package with every dimension and the mass shifted by the given
+/// amounts (never below zero)
+fn adjusted(package: &Package, dimension: f64, mass: f64) -> Package {
+    let shift = |value: Centimeters| Centimeters::new((value.value() + dimension).max(0.0));
+
+    Package {
+        width: shift(package.width),
+        height: shift(package.height),
+        length: shift(package.length),
+        mass: Kilograms::new((package.mass.value() + mass).max(0.0)),
+        shape: match package.shape {
+            Shape::Box => Shape::Box,
+            Shape::Cylinder { diameter, length } => Shape::Cylinder {
+                diameter: shift(diameter),
+                length: shift(length),
+            },
+            Shape::Irregular { bounding_box } => Shape::Irregular {
+                bounding_box: bounding_box.map(shift),
+            },
+        },
+        ..*package
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uncertainty() -> Uncertainty {
+        Uncertainty::new(Centimeters::new(0.5), Kilograms::new(0.1))
+    }
+
+    #[test]
+    fn test_certain_classification() {
+        let package = Package::new(
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+            Kilograms::new(10.0),
+        );
+        let result = decide_with_uncertainty(&SortRules::default(), &package, &uncertainty());
+
+        assert_eq!(result.decision.category, SortCategory::Standard);
+        assert_eq!(result.possible, vec![SortCategory::Standard]);
+        assert!(!result.needs_remeasure());
+    }
+
+    #[test]
+    fn test_borderline_classification() {
+        // 149.8 cm could be 150 cm, and 19.95 kg could be 20 kg
+        let package = Package::new(
+            Centimeters::new(149.8),
+            Centimeters::new(20.0),
+            Centimeters::new(20.0),
+            Kilograms::new(19.95),
+        );
+        let result = decide_with_uncertainty(&SortRules::default(), &package, &uncertainty());
+
+        assert_eq!(result.decision.category, SortCategory::Standard);
+        assert_eq!(
+            result.possible,
+            vec![
+                SortCategory::Standard,
+                SortCategory::Special,
+                SortCategory::Rejected
+            ]
+        );
+        assert_eq!(result.confidence, Confidence::Borderline);
+        assert!(result.needs_remeasure());
+    }
+
+    #[test]
+    fn test_too_small_and_bulky_within_uncertainty() {
+        // Too small at 1.7 cm and bulky at 150.3 cm, but standard as measured
+        let rules = SortRules {
+            min_dimension: Some(Centimeters::new(2.0)),
+            ..SortRules::default()
+        };
+        let package = Package::new(
+            Centimeters::new(2.2),
+            Centimeters::new(30.0),
+            Centimeters::new(149.8),
+            Kilograms::new(10.0),
+        );
+        let result = decide_with_uncertainty(&rules, &package, &uncertainty());
+
+        assert_eq!(result.decision.category, SortCategory::Standard);
+        assert_eq!(
+            result.possible,
+            vec![SortCategory::Standard, SortCategory::Special]
+        );
+        assert!(result.needs_remeasure());
+
+        // At exactly the minimum the package isn't too small
+        let package = Package::new(
+            Centimeters::new(2.5),
+            Centimeters::new(30.0),
+            Centimeters::new(50.0),
+            Kilograms::new(10.0),
+        );
+        let result = decide_with_uncertainty(&rules, &package, &uncertainty());
+        assert_eq!(result.possible, vec![SortCategory::Standard]);
+    }
+
+    #[test]
+    fn test_dense_band_within_uncertainty() {
+        // Dense only when measured both smaller and heavier
+        let rules = SortRules {
+            dense_threshold: Some(1_000.0),
+            ..SortRules::default()
+        };
+        let package = Package::new(
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Centimeters::new(10.0),
+            Kilograms::new(0.95),
+        );
+        let result = decide_with_uncertainty(&rules, &package, &uncertainty());
+        assert_eq!(result.decision.category, SortCategory::Standard);
+        assert_eq!(
+            result.possible,
+            vec![SortCategory::Standard, SortCategory::Special]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Rendering dimensions and masses in metric and/or imperial units.
+//!
+//! Only output is affected; rules and inputs always use centimeters and
+//! kilograms.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::numbers::NumberFormat;
+use crate::{Centimeters, Kilograms};
+
+/// Centimeters per inch
+pub const CM_PER_INCH: f64 = 2.54;
+
+/// Kilograms per pound
+pub const KG_PER_POUND: f64 = 0.453_592_37;
+
+/// The unit system measurements are rendered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitSystem {
+    /// Centimeters and kilograms
+    #[default]
+    Metric,
+    /// Inches and pounds
+    Imperial,
+    /// Metric followed by imperial, e.g. `160 cm / 63.0 in`
+    Both,
+}
+
+impl UnitSystem {
+    /// Returns the setting name of the unit system
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "metric",
+            UnitSystem::Imperial => "imperial",
+            UnitSystem::Both => "both",
+        }
+    }
+
+    /// Renders a length, e.g. `160 cm / 63.0 in`
+    pub fn length(&self, length: Centimeters) -> String {
+        self.dimensions(&[length])
+    }
+
+    /// Renders several lengths sharing one unit, e.g. `160x50x50 cm`
+    pub fn dimensions(&self, dimensions: &[Centimeters]) -> String {
+        self.dimensions_in(dimensions, &NumberFormat::default())
+    }
+
+    /// Renders several lengths with numbers in the given format, e.g.
+    /// `1.200x50x50 cm`
+    pub fn dimensions_in(&self, dimensions: &[Centimeters], format: &NumberFormat) -> String {
+        let metric = || {
+            let values: Vec<String> = dimensions
+                .iter()
+                .map(|d| format.format(d.value(), None))
+                .collect();
+            format!("{} cm", values.join("x"))
+        };
+        let imperial = || {
+            let values: Vec<String> = dimensions
+                .iter()
+                .map(|d| format.format(d.value() / CM_PER_INCH, Some(1)))
+                .collect();
+            format!("{} in", values.join("x"))
+        };
+        self.render(metric, imperial)
+    }
+
+    /// Renders a mass, e.g. `25 kg / 55.1 lb`
+    pub fn mass(&self, mass: Kilograms) -> String {
+        self.mass_in(mass, &NumberFormat::default())
+    }
+
+    /// Renders a mass with numbers in the given format, e.g. `2,5 kg`
+    pub fn mass_in(&self, mass: Kilograms, format: &NumberFormat) -> String {
+        self.render(
+            || format!("{} kg", format.format(mass.value(), None)),
+            || format!("{} lb", format.format(mass.value() / KG_PER_POUND, Some(1))),
+        )
+    }
+
+    fn render(&self, metric: impl Fn() -> String, imperial: impl Fn() -> String) -> String {
+        match self {
+            UnitSystem::Metric => metric(),
+            UnitSystem::Imperial => imperial(),
+            UnitSystem::Both => format!("{} / {}", metric(), imperial()),
+        }
+    }
+}
+
+impl fmt::Display for UnitSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for UnitSystem {
+    type Err = ParseUnitSystemError;
+
+    /// Parses `metric`, `imperial` or `both` (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [UnitSystem::Metric, UnitSystem::Imperial, UnitSystem::Both]
+            .into_iter()
+            .find(|units| units.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ParseUnitSystemError(s.to_string()))
+    }
+}
+
+/// Error returned when a string isn't a known unit system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUnitSystemError(String);
+
+impl fmt::Display for ParseUnitSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown unit system {:?}: expected metric, imperial or both",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseUnitSystemError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_units() {
+        let length = Centimeters::new(160.0);
+        assert_eq!(UnitSystem::Metric.length(length), "160 cm");
+        assert_eq!(UnitSystem::Imperial.length(length), "63.0 in");
+        assert_eq!(UnitSystem::Both.length(length), "160 cm / 63.0 in");
+
+        let dims = [
+            Centimeters::new(160.0),
+            Centimeters::new(50.0),
+            Centimeters::new(50.0),
+        ];
+        assert_eq!(
+            UnitSystem::Both.dimensions(&dims),
+            "160x50x50 cm / 63.0x19.7x19.7 in"
+        );
+        assert_eq!(
+            UnitSystem::Both.mass(Kilograms::new(25.0)),
+            "25 kg / 55.1 lb"
+        );
+    }
+
+    #[test]
+    fn test_render_units_in_format() {
+        let format = NumberFormat::COMMA;
+        let dimensions = [Centimeters::new(1_200.0), Centimeters::new(50.5)];
+        assert_eq!(
+            UnitSystem::Both.dimensions_in(&dimensions, &format),
+            "1.200x50,5 cm / 472,4x19,9 in"
+        );
+        assert_eq!(
+            UnitSystem::Metric.mass_in(Kilograms::new(2.5), &format),
+            "2,5 kg"
+        );
+    }
+
+    #[test]
+    fn test_parse_unit_system() {
+        assert_eq!("Imperial".parse(), Ok(UnitSystem::Imperial));
+        assert_eq!("both".parse(), Ok(UnitSystem::Both));
+        assert!("furlongs".parse::<UnitSystem>().is_err());
+    }
+}
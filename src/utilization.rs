@@ -0,0 +1,135 @@
+use crate::{Centimeters, Kilograms, Package};
+
+/// A truck, trailer, shipping container or carton with its internal
+/// dimensions and payload limit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Container {
+    pub width: Centimeters,
+    pub height: Centimeters,
+    pub length: Centimeters,
+    pub max_mass: Kilograms,
+}
+
+impl Container {
+    /// Creates a new container with the given internal dimensions and payload
+    pub fn new(
+        width: Centimeters,
+        height: Centimeters,
+        length: Centimeters,
+        max_mass: Kilograms,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            length,
+            max_mass,
+        }
+    }
+
+    /// Calculates the internal volume of the container in cubic centimeters
+    pub fn volume(&self) -> f64 {
+        self.width.value() * self.height.value() * self.length.value()
+    }
+}
+
+/// Which limit a container runs out of first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingConstraint {
+    Volume,
+    Mass,
+}
+
+/// How full a container would be with a batch of packages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Utilization {
+    /// Combined volume of the packages in cubic centimeters
+    pub total_volume: f64,
+    /// Combined mass of the packages
+    pub total_mass: Kilograms,
+    /// Share of the container volume used, as a percentage
+    pub volume_percent: f64,
+    /// Share of the container payload used, as a percentage
+    pub mass_percent: f64,
+    /// The limit that is closest to being reached
+    pub binding: BindingConstraint,
+}
+
+impl Utilization {
+    /// Checks if the batch stays within both the volume and payload limits.
+    ///
+    /// This compares totals only; it doesn't check that the packages can
+    /// actually be arranged inside the container.
+    pub fn fits(&self) -> bool {
+        self.volume_percent <= 100.0 && self.mass_percent <= 100.0
+    }
+}
+
+/// Calculates how much of a container's volume and payload a batch of
+/// packages uses, and which of the two is the binding constraint
+pub fn utilization(packages: &[Package], container: &Container) -> Utilization {
+    let total_volume: f64 = packages.iter().map(Package::volume).sum();
+    let total_mass: f64 = packages.iter().map(|p| p.mass.value()).sum();
+
+    let volume_percent = total_volume / container.volume() * 100.0;
+    let mass_percent = total_mass / container.max_mass.value() * 100.0;
+    let binding = if mass_percent > volume_percent {
+        BindingConstraint::Mass
+    } else {
+        BindingConstraint::Volume
+    };
+
+    Utilization {
+        total_volume,
+        total_mass: Kilograms::new(total_mass),
+        volume_percent,
+        mass_percent,
+        binding,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container() -> Container {
+        // 2 m x 2 m x 5 m with a 1,000 kg payload
+        Container::new(
+            Centimeters::new(200.0),
+            Centimeters::new(200.0),
+            Centimeters::new(500.0),
+            Kilograms::new(1_000.0),
+        )
+    }
+
+    fn package(side: f64, mass: f64) -> Package {
+        Package::new(
+            Centimeters::new(side),
+            Centimeters::new(side),
+            Centimeters::new(side),
+            Kilograms::new(mass),
+        )
+    }
+
+    #[test]
+    fn test_volume_bound_utilization() {
+        let packages = vec![package(100.0, 50.0); 10];
+        let result = utilization(&packages, &container());
+
+        assert_eq!(result.total_volume, 10_000_000.0);
+        assert_eq!(result.total_mass, Kilograms::new(500.0));
+        assert_eq!(result.volume_percent, 50.0);
+        assert_eq!(result.mass_percent, 50.0);
+        assert_eq!(result.binding, BindingConstraint::Volume);
+        assert!(result.fits());
+    }
+
+    #[test]
+    fn test_mass_bound_utilization() {
+        let packages = vec![package(50.0, 100.0); 12];
+        let result = utilization(&packages, &container());
+
+        assert_eq!(result.mass_percent, 120.0);
+        assert_eq!(result.binding, BindingConstraint::Mass);
+        assert!(!result.fits());
+    }
+}
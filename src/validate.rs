@@ -0,0 +1,180 @@
+//! Package construction from raw records, reporting every bad field at once.
+
+use std::fmt;
+
+use crate::numbers::{parse_number, ParseMode, ParseNumberError};
+use crate::{Centimeters, Kilograms, Package};
+
+/// Fields a package record must have
+pub const FIELDS: [&str; 4] = ["width", "height", "length", "mass"];
+
+/// A problem with one field of a record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// Name of the field, e.g. `"width"`
+    pub field: String,
+    pub kind: FieldErrorKind,
+}
+
+/// What was wrong with a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldErrorKind {
+    /// The field is absent
+    Missing,
+    /// The field was given more than once
+    Duplicate,
+    /// The field isn't a number
+    Invalid(ParseNumberError),
+    /// The value is infinite or NaN
+    NotFinite,
+    /// A dimension is zero or negative, or the mass is negative
+    OutOfRange,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            FieldErrorKind::Missing => write!(f, "{}: missing", self.field),
+            FieldErrorKind::Duplicate => write!(f, "{}: given more than once", self.field),
+            FieldErrorKind::Invalid(err) => write!(f, "{}: {}", self.field, err),
+            FieldErrorKind::NotFinite => write!(f, "{}: not a finite number", self.field),
+            FieldErrorKind::OutOfRange => write!(f, "{}: out of range", self.field),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Builds a package from its numeric measurements, checking every one
+pub fn validate(
+    width: f64,
+    height: f64,
+    length: f64,
+    mass: f64,
+) -> Result<Package, Vec<FieldError>> {
+    let values = [width, height, length, mass];
+    let errors: Vec<FieldError> = FIELDS
+        .iter()
+        .zip(values)
+        .filter_map(|(&field, value)| {
+            check(field, value).err().map(|kind| FieldError {
+                field: field.to_string(),
+                kind,
+            })
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(Package::new(
+            Centimeters::new(width),
+            Centimeters::new(height),
+            Centimeters::new(length),
+            Kilograms::new(mass),
+        ))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Builds a package from `(field, text)` pairs, e.g. a CSV row zipped with
+/// its header. Fields other than [`FIELDS`] are ignored.
+pub fn package_from_record<'a>(
+    record: impl IntoIterator<Item = (&'a str, &'a str)>,
+    mode: ParseMode,
+) -> Result<Package, Vec<FieldError>> {
+    let mut values: [Option<f64>; 4] = [None; 4];
+    let mut seen = [false; 4];
+    let mut errors = Vec::new();
+    let mut error = |field: &str, kind| {
+        errors.push(FieldError {
+            field: field.to_string(),
+            kind,
+        })
+    };
+
+    for (field, text) in record {
+        let Some(index) = FIELDS.iter().position(|&f| f == field) else {
+            continue;
+        };
+        if seen[index] {
+            error(field, FieldErrorKind::Duplicate);
+            continue;
+        }
+        seen[index] = true;
+
+        match parse_number(text, mode) {
+            Ok(parsed) => match check(field, parsed.value) {
+                Ok(()) => values[index] = Some(parsed.value),
+                Err(kind) => error(field, kind),
+            },
+            Err(err) => error(field, FieldErrorKind::Invalid(err)),
+        }
+    }
+    for (index, field) in FIELDS.iter().enumerate() {
+        if !seen[index] {
+            error(field, FieldErrorKind::Missing);
+        }
+    }
+
+    match values {
+        [Some(width), Some(height), Some(length), Some(mass)] if errors.is_empty() => {
+            validate(width, height, length, mass)
+        }
+        _ => Err(errors),
+    }
+}
+
+pub(crate) fn check(field: &str, value: f64) -> Result<(), FieldErrorKind> {
+    if !value.is_finite() {
+        Err(FieldErrorKind::NotFinite)
+    } else if value < 0.0 || (field != "mass" && value == 0.0) {
+        Err(FieldErrorKind::OutOfRange)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(errors: &[FieldError]) -> Vec<&str> {
+        errors.iter().map(|e| e.field.as_str()).collect()
+    }
+
+    #[test]
+    fn test_validate_collects_all_errors() {
+        assert!(validate(10.0, 10.0, 10.0, 0.0).is_ok());
+
+        let errors = validate(0.0, 10.0, f64::NAN, -1.0).unwrap_err();
+        assert_eq!(fields(&errors), ["width", "length", "mass"]);
+        assert_eq!(errors[1].kind, FieldErrorKind::NotFinite);
+    }
+
+    #[test]
+    fn test_package_from_record() {
+        let record = [
+            ("id", "PKG-1"),
+            ("width", "10"),
+            ("height", "20"),
+            ("length", "30"),
+            ("mass", "2.5"),
+        ];
+        let package = package_from_record(record, ParseMode::Strict).unwrap();
+        assert_eq!(package.length, Centimeters::new(30.0));
+
+        let record = [
+            ("width", "ten"),
+            ("height", "-2"),
+            ("width", "10"),
+            ("mass", "1"),
+        ];
+        let errors = package_from_record(record, ParseMode::Strict).unwrap_err();
+        assert_eq!(fields(&errors), ["width", "height", "width", "length"]);
+        assert!(matches!(errors[0].kind, FieldErrorKind::Invalid(_)));
+        assert_eq!(errors[1].kind, FieldErrorKind::OutOfRange);
+        assert_eq!(errors[2].kind, FieldErrorKind::Duplicate);
+        assert_eq!(errors[3].kind, FieldErrorKind::Missing);
+        assert_eq!(errors[3].to_string(), "length: missing");
+    }
+}